@@ -76,6 +76,7 @@ impl Context {
                 global::Inner::ManualError(ManualError::NoSuchValue(value)) => format!("'{}' is not defined", value),
                 global::Inner::ManualError(ManualError::OperationNotValidForType(op)) => format!("The operation '{}' was attempted on an invalid type", op),
                 global::Inner::ManualError(ManualError::EmptyResultSet(query)) => format!("The query '{}' returned no results", query),
+                global::Inner::ParserError(err) => err.to_string(),
                 err => format!("Miscellaneous Error: {:?}", err)
             });
 
@@ -92,7 +93,15 @@ impl Context {
     pub fn parse_str(&self, expr: String) -> Vec<Token> {
         fn flatten(token: &mut Value) -> Option<Vec<Token>> {
             match token {
-                Value::Expression(parse::expression::Expression { operator, ref mut operands }) if operands.len() > 0 => {
+                Value::Expression(parse::expression::Expression { operator, ref mut operands, fixity: parse::expression::Fixity::Prefix, .. }) => {
+                    Some(iter::once(Token::new(operator.clone(), TokenType::Operator))
+                        .chain(operands
+                            .iter_mut()
+                            .filter_map(flatten)
+                            .flat_map(|i| i))
+                        .collect())
+                },
+                Value::Expression(parse::expression::Expression { operator, ref mut operands, .. }) if operands.len() > 0 => {
                     let (first, rest) = operands.split_first_mut()?;
 
                     Some(flatten(first)?
@@ -105,7 +114,7 @@ impl Context {
                         .collect())
                 },
                 Value::Expression(parse::expression::Expression { operator, .. }) => Some(vec![Token::new(operator.clone(), TokenType::Operator)]),
-                Value::Literal(lit) => Some(vec![match lit {
+                Value::Literal(lit) => Some(vec![match &lit.node {
                     Literal::Nothing => Token::new("nothing".to_owned(), TokenType::Nothing),
                     Literal::Bool(bool) => Token::new(format!("{}", bool), TokenType::Bool),
                     Literal::Name(name) => Token::new(name.clone(), TokenType::Name),
@@ -113,7 +122,7 @@ impl Context {
                     Literal::Number(num) => Token::new(format!("{}", num), TokenType::Num),
                     Literal::Address(addr) => Token::new(format!("{{{content}}}", content=addr.query), TokenType::Address),
                 }]),
-                Value::Call(parse::call::Call { name, arguments }) => {
+                Value::Call(parse::call::Call { name, arguments, .. }) => {
                     Some(flatten(name)?
                         .into_iter()
                         .chain(iter::once(Token::new("(".to_owned(), TokenType::LParen)))
@@ -125,8 +134,16 @@ impl Context {
                         .collect())
                 },
                 Value::Access(_) => Some(vec![]),
+                Value::Index(_) => Some(vec![]),
+                Value::Pipeline(_) => Some(vec![]),
+                Value::Template(_) => Some(vec![]),
+                Value::Spread(_) => Some(vec![]),
+                Value::Match(_) => Some(vec![]),
                 Value::List(_) => Some(vec![]),
-                Value::AssociativeArray(_) => Some(vec![])
+                Value::AssociativeArray(_) => Some(vec![]),
+                Value::Conditional(_) => Some(vec![]),
+                Value::Lambda(_) => Some(vec![]),
+                Value::Let(_) => Some(vec![])
             }
         }
 
@@ -62,10 +62,15 @@ pub(crate) fn js_value_to_object(value: JsValue) -> Option<Object> {
 }
 
 
+/// The most items a range is expanded into when converted to an array. Larger ranges fail to convert rather than
+/// exhausting memory.
+const MAX_RANGE_LENGTH: usize = 1 << 24;
+
 /// This function attempts to convert a native value into its JS equivalent.
 ///
 /// * `js_sys::String`s => `expression::Object::String`
 /// * `js_sys::Array`s => `expression::Object::List`
+/// * `js_sys::Array`s => `expression::Object::Range`, of at most `MAX_RANGE_LENGTH` items
 /// * `js_sys::Object`s => `expression::Object::AssociativeArray`
 /// * `js_sys::Object + { [Symbol.address]: Address }`s => `expression::Object::Address` !
 /// * `js_sys::Null` => `expression::Object::Nothing`
@@ -81,6 +86,9 @@ pub(crate) fn value_to_js_object(value: Object) -> Option<JsValue> {
         Object::String(str) => JsValue::from_str(&str),
         Object::List(list) => JsValue::from(js_sys::Array::from_iter(list.into_iter()
             .flat_map(value_to_js_object))),
+        Object::Range(range) if range.len() > MAX_RANGE_LENGTH => None?,
+        Object::Range(range) => JsValue::from(js_sys::Array::from_iter(range.iter()
+            .map(|number| JsValue::from(js_sys::Number::from(number))))),
         Object::AssociativeArray(arr) => {
             let key_map = js_sys::Object::new();
 
@@ -94,7 +102,7 @@ pub(crate) fn value_to_js_object(value: Object) -> Option<JsValue> {
         Object::Nothing => JsValue::null(),
         Object::Function(function) => JsClosure::new(move |_args| -> JsValue {
             wasm_bindgen::throw_str("Fuck you");
-            function(vec![])
+            function(vec![], Default::default())
                 .map(value_to_js_object)
                 .unwrap_throw()
                 .unwrap_throw()
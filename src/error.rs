@@ -4,7 +4,6 @@ macro_rules! multi_error {
         pub mod $name {
             #[cfg(test)]
             use backtrace::Backtrace;
-            use alloc::string::String;
 
             #[derive(Debug)]
             pub enum Inner {
@@ -65,7 +64,7 @@ multi_error! { global();
     ManualError = crate::error::ManualError;
     // IoError = std::io::Error;
 
-    ParserError = nom::Err<nom::error::Error<String>>
+    ParserError = crate::parse::error::ParseError
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -92,6 +91,8 @@ pub enum ManualError {
     ConversionFailed,
     ExpectedType(String),
     EmptyResultSet(String),
+    UnexpectedArgument(String),
+    NoMatchingPattern(String),
 
     OtherError(String)
 }
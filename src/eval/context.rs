@@ -1,7 +1,9 @@
 use crate::error::*;
-use crate::eval::globals::get_standard_globals;
+use crate::eval::globals::{format_number, get_standard_globals, to_string};
 use crate::eval::operators::get_standard_operators;
+use crate::eval::scope::Scope;
 use crate::eval::Object;
+use crate::parse::expression::OperatorSyntax;
 use crate::parse::objects::*;
 use crate::DataSource;
 use alloc::borrow::ToOwned;
@@ -31,24 +33,57 @@ use nom::lib::std::collections::HashMap;
 /// assert_eq!(cx.evaluate(r#"2*5"#).unwrap(), 10.0);
 /// ```
 pub struct Context<Provider: DataSource> {
-    globals: HashMap<String, Object>,
+    /// Shared with the lambdas created by evaluations, and copied only when changed while they still exist.
+    globals: Rc<HashMap<String, Object>>,
     data_provider: Box<Provider>,
-    pub(crate) operators: HashMap<String, Operator>,
+    pub(crate) operators: Rc<HashMap<(String, Fixity), Operator>>,
+}
+
+/// An operand which has not been evaluated yet. Lazy operators decide for themselves which of their operands to
+/// evaluate, and how often.
+pub struct Thunk<'a>(Box<dyn Fn() -> Result<Object> + 'a>);
+
+impl Thunk<'_> {
+    /// Evaluates the operand. The operand is evaluated again on every call.
+    pub fn evaluate(&self) -> Result<Object> {
+        (self.0)()
+    }
+}
+
+#[derive(Clone)]
+enum Handler {
+    Eager(Rc<Box<dyn Fn(&[Object]) -> Result<Object>>>),
+    Lazy(Rc<Box<dyn Fn(&[Thunk]) -> Result<Object>>>),
 }
 
 #[derive(Clone)]
 pub struct Operator {
-    handler: Rc<Box<dyn Fn(&[Object]) -> Result<Object>>>,
+    handler: Handler,
     symbol: String,
     pub(crate) precedence: i64,
     operands: usize,
+    fixity: Fixity,
+    associativity: Associativity,
+}
+
+impl Operator {
+    pub(crate) fn syntax(&self) -> OperatorSyntax {
+        OperatorSyntax {
+            symbol: self.symbol.clone(),
+            precedence: self.precedence,
+            fixity: self.fixity,
+            associativity: self.associativity,
+        }
+    }
 }
 
 pub struct OperatorBuilder {
-    handler: Option<Box<dyn Fn(&[Object]) -> Result<Object>>>,
+    handler: Option<Handler>,
     symbol: Option<String>,
     precedence: i64,
     operands: usize,
+    fixity: Option<Fixity>,
+    associativity: Associativity,
 }
 
 impl OperatorBuilder {
@@ -58,11 +93,39 @@ impl OperatorBuilder {
             symbol: None,
             precedence: i64::MAX,
             operands: 2,
+            fixity: None,
+            associativity: Associativity::Left,
         }
     }
 
     pub fn handler(mut self, handler: impl Fn(&[Object]) -> Result<Object> + 'static) -> Self {
-        self.handler = Some(Box::new(handler));
+        self.handler = Some(Handler::Eager(Rc::new(Box::new(handler))));
+        self
+    }
+
+    /// # Lazy Operators
+    /// Lazy operators receive their operands unevaluated, which allows them to short-circuit or otherwise control
+    /// evaluation, such as this operator which only evaluates its right-hand side when the left-hand side fails.
+    ///
+    /// ```rust
+    /// use expression::eval::context::OperatorBuilder;
+    /// use expression::eval::Object;
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).with_operator(
+    ///     OperatorBuilder::new()
+    ///         .symbol("otherwise")
+    ///         .precedence(1)
+    ///         .lazy_handler(|args| args[0].evaluate().or_else(|_| args[1].evaluate()))
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(cx.evaluate(r#"undefined_value otherwise 5"#).unwrap(), 5.0);
+    /// assert_eq!(cx.evaluate(r#"1 otherwise undefined_value"#).unwrap(), 1.0);
+    /// ```
+    pub fn lazy_handler(mut self, handler: impl Fn(&[Thunk]) -> Result<Object> + 'static) -> Self {
+        self.handler = Some(Handler::Lazy(Rc::new(Box::new(handler))));
         self
     }
 
@@ -76,19 +139,98 @@ impl OperatorBuilder {
         self
     }
 
+    /// Operators taking a single operand are written as prefix operators unless a different [`Fixity`] is given.
     pub fn operands(mut self, operands: usize) -> Self {
         self.operands = operands;
         self
     }
 
+    /// # Fixity
+    /// Declares where the operator is written relative to its operands. Prefix and postfix operators receive exactly
+    /// one operand, infix operators receive two.
+    ///
+    /// ```rust
+    /// use expression::eval::context::OperatorBuilder;
+    /// use expression::eval::Object;
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    /// use expression::Fixity;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).with_operator(
+    ///     OperatorBuilder::new()
+    ///         .symbol("%")
+    ///         .fixity(Fixity::Postfix)
+    ///         .precedence(30)
+    ///         .handler(|args| match args {
+    ///             [Object::Number(x)] => Ok(Object::Number(x / 100.0)),
+    ///             _ => Ok(Object::Nothing),
+    ///         })
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(cx.evaluate(r#"50%"#).unwrap(), 0.5);
+    /// assert_eq!(cx.evaluate(r#"7 % 4"#).unwrap(), 3.0);
+    /// ```
+    pub fn fixity(mut self, fixity: Fixity) -> Self {
+        self.operands = match fixity {
+            Fixity::Infix => 2,
+            Fixity::Prefix | Fixity::Postfix => 1,
+        };
+        self.fixity = Some(fixity);
+        self
+    }
+
+    /// # Associativity
+    /// Declares how a chain of infix operators of equal precedence is grouped. Operators are left-associative unless
+    /// stated otherwise.
+    ///
+    /// ```rust
+    /// use expression::eval::context::OperatorBuilder;
+    /// use expression::eval::Object;
+    /// use expression::Associativity;
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).with_operator(
+    ///     OperatorBuilder::new()
+    ///         .symbol("~>")
+    ///         .associativity(Associativity::Right)
+    ///         .precedence(10)
+    ///         .handler(|args| match args {
+    ///             [Object::Number(l), Object::Number(r)] => Ok(Object::Number(l - r)),
+    ///             _ => Ok(Object::Nothing),
+    ///         })
+    ///         .build(),
+    /// );
+    ///
+    /// assert_eq!(cx.evaluate(r#"10 - 2 - 3"#).unwrap(), 5.0);
+    /// assert_eq!(cx.evaluate(r#"10 ~> 2 ~> 3"#).unwrap(), 11.0);
+    /// ```
+    pub fn associativity(mut self, associativity: Associativity) -> Self {
+        self.associativity = associativity;
+        self
+    }
+
     pub fn build(self) -> Operator {
+        let fixity = self.fixity.unwrap_or(match self.operands {
+            1 => Fixity::Prefix,
+            _ => Fixity::Infix,
+        });
+
+        match (fixity, self.operands) {
+            (Fixity::Infix, 2) | (Fixity::Prefix | Fixity::Postfix, 1) => (),
+            _ => panic!("{:?} operators cannot take {} operands", fixity, self.operands),
+        }
+
         if let Some(handler) = self.handler {
             if let Some(symbol) = self.symbol {
                 return Operator {
-                    handler: Rc::new(handler),
+                    handler,
                     symbol,
                     precedence: self.precedence,
                     operands: self.operands,
+                    fixity,
+                    associativity: self.associativity,
                 };
             }
         }
@@ -101,11 +243,17 @@ impl<Provider> Context<Provider>
 where
     Provider: DataSource,
 {
+    /// Creates a context with the standard globals and operators. From loosest to tightest, the standard infix
+    /// operators are `??`, `||` and `or`, `&&` and `and`, `==` and `!=`, `<`, `>` and `in`, `step`, `..`, `+` and `-`,
+    /// `*`, `/`, `%` and `mod`, and finally `^`. So `a == b && c || d` is read as `((a == b) && c) || d`.
+    ///
+    /// Ranges bind looser than arithmetic so their bounds may be computed, as in `0..len(list) - 1`. A range must
+    /// therefore be parenthesised to be combined with other values, as in `(1..3) + [4]`.
     pub fn new(provider: Provider) -> Self {
         Self {
-            globals: get_standard_globals().into_iter().collect(),
+            globals: Rc::new(get_standard_globals().into_iter().collect()),
             data_provider: Box::new(provider),
-            operators: get_standard_operators().into_iter().map(|op| (op.symbol.clone(), op)).collect(),
+            operators: Rc::new(get_standard_operators().into_iter().map(|op| ((op.symbol.clone(), op.fixity), op)).collect()),
         }
     }
 
@@ -125,7 +273,7 @@ where
     /// assert_eq!(cx.evaluate(r#"PI"#).unwrap(), std::f64::consts::PI);
     /// ```
     pub fn with_global(mut self, name: impl AsRef<str>, global: Object) -> Self {
-        self.globals.insert(name.as_ref().to_string(), global);
+        Rc::make_mut(&mut self.globals).insert(name.as_ref().to_string(), global);
         self
     }
 
@@ -145,7 +293,7 @@ where
     /// assert_eq!(cx.evaluate(r#"PI"#).unwrap(), std::f64::consts::PI);
     /// ```
     pub fn push_global(&mut self, name: impl AsRef<str>, global: Object) {
-        self.globals.insert(name.as_ref().to_string(), global);
+        Rc::make_mut(&mut self.globals).insert(name.as_ref().to_string(), global);
     }
 
     /// # Operator Overloads
@@ -170,10 +318,10 @@ where
     ///         .build(),
     /// );
     ///
-    /// assert_eq!(cx.evaluate(r#"2~10"#).unwrap(), Object::Nothing);
+    /// assert_eq!(cx.evaluate(r#"~10"#).unwrap(), Object::Nothing);
     /// ```
     pub fn with_operator(mut self, operator: Operator) -> Self {
-        self.operators.insert(operator.symbol.clone(), operator);
+        Rc::make_mut(&mut self.operators).insert((operator.symbol.clone(), operator.fixity), operator);
         self
     }
 
@@ -200,116 +348,298 @@ where
     ///         .build(),
     /// );
     ///
-    /// assert_eq!(cx.evaluate(r#"2~10"#).unwrap(), Object::Nothing);
+    /// assert_eq!(cx.evaluate(r#"~10"#).unwrap(), Object::Nothing);
     /// ```
     pub fn push_operator(&mut self, operator: Operator) {
-        self.operators.insert(operator.symbol.clone(), operator);
+        Rc::make_mut(&mut self.operators).insert((operator.symbol.clone(), operator.fixity), operator);
     }
 
     pub fn call_object(&self, object: Object, arguments: &[Object]) -> Result<Object> {
-        if let Object::Function(obj) = object {
-            obj(arguments.iter().cloned().collect())
-        } else {
-            Err(ManualError::CannotCallNonFunctionObject().into())
-        }
+        self.call_object_with_named(object, arguments, HashMap::new())
+    }
+
+    /// Calls `object` with named arguments as well as positional ones, as for `f(x, precision = 2)`.
+    pub fn call_object_with_named(&self, object: Object, arguments: &[Object], named: HashMap<String, Object>) -> Result<Object> {
+        object.call(arguments.to_vec(), named)
     }
 
     fn query(&self, query: impl AsRef<str>) -> Option<Object> {
         Some(self.data_provider.query(query)?)
     }
 
-    fn evaluate_value(&self, value: Value) -> Result<Object> {
+    pub fn provider(&self) -> &Provider {
+        self.data_provider.as_ref()
+    }
+
+    pub fn provider_mut(&mut self) -> &mut Provider {
+        self.data_provider.as_mut()
+    }
+
+    pub(super) fn evaluate_value(&self, value: &Value, scope: &Scope) -> Result<Object> {
         match value {
-            Value::Expression(Expression { operands, operator }) =>
-                if let Some(operator) = self.operators.get(&operator) {
-                    let operands = operands
-                        .into_iter()
-                        .map(|operand| self.evaluate_value(operand))
-                        .collect::<Result<Vec<_>>>()?;
-
-                    Ok((operator.handler)(&operands)?)
+            Value::Expression(Expression { operands, operator, fixity, .. }) =>
+                if let Some(operator) = self.operators.get(&(operator.clone(), *fixity)) {
+                    if operands.len() != operator.operands {
+                        return Err(ManualError::InsufficientOperands(operator.symbol.clone()).into());
+                    }
+
+                    match &operator.handler {
+                        Handler::Eager(handler) => handler(&operands
+                            .iter()
+                            .map(|operand| self.evaluate_value(operand, scope))
+                            .collect::<Result<Vec<_>>>()?),
+                        Handler::Lazy(handler) => handler(&operands
+                            .iter()
+                            .map(|operand| Thunk(Box::new(move || self.evaluate_value(operand, scope))))
+                            .collect::<Vec<_>>()),
+                    }
                 } else {
-                    Err(ManualError::NoSuchOperator(operator).into())
+                    Err(ManualError::NoSuchOperator(operator.clone()).into())
                 },
 
-            Value::Literal(literal) => match literal {
+            Value::Literal(literal) => match &literal.node {
                 Literal::Nothing => Ok(Object::Nothing),
-                Literal::Bool(bool) => Ok(Object::Boolean(bool)),
-                Literal::Number(number) => Ok(Object::Number(number)),
-                Literal::String(string) => Ok(Object::String(string)),
-                Literal::Name(name) => self
-                    .globals
+                Literal::Bool(bool) => Ok(Object::Boolean(*bool)),
+                Literal::Number(number) => Ok(Object::Number(*number)),
+                Literal::String(string) => Ok(Object::String(string.clone())),
+                Literal::Name(name) => scope
                     .get(name.as_str())
+                    .or_else(|| self.globals.get(name.as_str()))
                     .cloned()
                     .ok_or(ManualError::NoSuchValue(name.clone()).into()),
 
                 Literal::Address(address) => Ok(self.query(&address.query).unwrap_or(Object::Nothing)),
             },
 
-            Value::Call(Call { name, arguments }) => self.call_object(
-                self.evaluate_value(*name)?,
-                &arguments
-                    .into_iter()
-                    .map(|i| self.evaluate_value(i))
-                    .collect::<Result<Vec<_>>>()?,
-            ),
-
-            Value::Access(Access { left, member }) => match (self.evaluate_value(*left), member) {
-                (Ok(Object::AssociativeArray(array)), Literal::Name(ref name) | Literal::String(ref name)) => array.get(name).cloned().ok_or(ManualError::NoSuchValue(name.clone()).into()),
-
-                (Ok(Object::List(list)), Literal::Name(ref name) | Literal::String(ref name)) => name
-                    .parse::<usize>()
-                    .ok()
-                    .and_then(|index| list.get(index))
-                    .cloned()
-                    .ok_or(ManualError::NoSuchValue(name.clone()).into()),
+            Value::Access(_) | Value::Index(_) | Value::Call(_) => self.evaluate_chain(value, scope)
+                .map(|object| object.unwrap_or(Object::Nothing)),
 
-                (Ok(Object::List(list)), Literal::Number(ref name)) => list
-                    .get(*name as usize)
-                    .cloned()
-                    .ok_or(ManualError::NoSuchValue(format!("{}", name)).into()),
+            Value::Conditional(Conditional { condition, then, otherwise, .. }) => match self.evaluate_value(condition, scope)? {
+                Object::Boolean(true) => self.evaluate_value(then, scope),
+                Object::Boolean(false) => self.evaluate_value(otherwise, scope),
+                obj => Err(ManualError::OperationNotValidForType(format!("Condition must be a boolean, found {}", obj.datatype())).into()),
+            },
+
+            Value::Lambda(Lambda { parameters, body, .. }) => Ok(self.function(parameters, body, scope, None)),
+
+            Value::Pipeline(Pipeline { value, function, arguments, named, .. }) => {
+                let value = self.evaluate_value(value, scope)?;
+                let function = self.evaluate_value(function, scope)?;
+                let (arguments, named) = self.evaluate_arguments(arguments, named, scope)?;
+
+                self.call_object_with_named(function, &core::iter::once(value).chain(arguments).collect::<Vec<_>>(), named)
+            },
+
+            Value::Spread(_) => Err(ManualError::OperationNotValidForType("Values may only be spread within lists, associative arrays and calls".to_owned()).into()),
+
+            Value::Template(Template { segments, .. }) => segments.iter()
+                .map(|segment| match segment {
+                    Segment::Text(text) => Ok(text.clone()),
+                    Segment::Value { value, format: None } => to_string(self.evaluate_value(value, scope)?),
+                    Segment::Value { value, format: Some(format) } => match self.evaluate_value(value, scope)? {
+                        Object::Number(number) => Ok(format_number(number, format)),
+                        obj => Err(ManualError::ExpectedType(format!("Formatted values must be numbers, found {}", obj.datatype())).into()),
+                    },
+                })
+                .collect::<Result<String>>()
+                .map(Object::String),
+
+            Value::Match(Match { value, arms, .. }) => {
+                let object = self.evaluate_value(value, scope)?;
+
+                for (pattern, body) in arms {
+                    let mut bindings = HashMap::new();
+
+                    match self.destructure(pattern, &object, scope, &mut bindings).map_err(Error::into_inner) {
+                        Ok(()) => return self.evaluate_value(body, &scope.with(bindings)),
+                        Err(global::Inner::ManualError(ManualError::NoMatchingPattern(_))) => continue,
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+
+                Err(ManualError::NoMatchingPattern(format!("No pattern matches {}", object)).into())
+            },
+
+            Value::Let(Let { bindings, body, .. }) => {
+                let scope = bindings.iter().try_fold(scope.clone(), |scope, (pattern, value)| {
+                    let value = self.evaluate_value(value, &scope)?;
+                    let mut bindings = HashMap::new();
+                    self.destructure(pattern, &value, &scope, &mut bindings)?;
+
+                    Ok::<_, Error>(scope.with(bindings))
+                })?;
+
+                self.evaluate_value(body, &scope)
+            },
+
+            Value::List(list) => Ok(Object::List(self.evaluate_items(&list.items, scope)?)),
+
+            Value::AssociativeArray(arr) => arr.items
+                .iter()
+                .try_fold(HashMap::new(), |mut array, entry| {
+                    match entry {
+                        Entry::Pair(Key::Name(key) | Key::String(key), value) => {
+                            array.insert(key.clone(), self.evaluate_value(value, scope)?);
+                        },
+                        Entry::Spread(Spread { value, .. }) => match self.evaluate_value(value, scope)? {
+                            Object::AssociativeArray(entries) => array.extend(entries),
+                            obj => return Err(ManualError::OperationNotValidForType(format!("Cannot spread {} into an associative array", obj.datatype())).into()),
+                        },
+                    }
+
+                    Ok(array)
+                })
+                .map(Object::AssociativeArray),
+        }
+    }
+
+    /// Creates the function for a lambda closing over `scope`. The lambda may be called after this evaluation has
+    /// finished, so rather than borrowing the data source it captures the values of the addresses it refers to.
+    fn function(&self, parameters: &Rc<[Pattern]>, body: &Rc<Value>, scope: &Scope, name: Option<&str>) -> Object {
+        let mut queries = Vec::new();
+        parameters.iter()
+            .flat_map(Pattern::defaults)
+            .chain(core::iter::once(body.as_ref()))
+            .for_each(|value| addresses(value, &mut queries));
+
+        let cx = Context {
+            globals: Rc::clone(&self.globals),
+            data_provider: Box::new(Captured(queries.into_iter()
+                .filter_map(|query| Some((query.to_owned(), self.query(query)?)))
+                .collect())),
+            operators: Rc::clone(&self.operators),
+        };
+
+        closure(Rc::new(cx), Rc::clone(parameters), Rc::clone(body), scope.clone(), name.map(Rc::from))
+    }
+
+    /// Evaluates a chain of accesses, indices and calls. `None` means an optional link found `nothing`, which skips the
+    /// remainder of the chain, so `a?.b.c` is `nothing` rather than an error when `a` is `nothing`.
+    fn evaluate_chain(&self, value: &Value, scope: &Scope) -> Result<Option<Object>> {
+        let (left, optional) = match value {
+            Value::Access(Access { left, optional, .. }) | Value::Index(Index { left, optional, .. }) => (left, *optional),
+            Value::Call(Call { name, optional, .. }) => (name, *optional),
+            value => return self.evaluate_value(value, scope).map(Some),
+        };
+
+        let Some(object) = self.evaluate_chain(left, scope)? else {
+            return Ok(None);
+        };
+
+        if optional && matches!(object, Object::Nothing) {
+            return Ok(None);
+        }
 
-                (Ok(obj), _) => Err(ManualError::OperationNotValidForType(format!("Object of type '{}' does not exhibit any accessible members", obj.datatype())).into()),
-                (Err(err), _) => Err(err),
+        match value {
+            Value::Access(Access { member, .. }) => member_of(object, &match &member.node {
+                Literal::Number(index) => Object::Number(*index),
+                Literal::Name(name) | Literal::String(name) => Object::String(name.clone()),
+                literal => return Err(ManualError::OperationNotValidForType(format!("{:?} is not a valid member", literal)).into()),
+            }),
+            Value::Index(Index { index, .. }) => member_of(object, &self.evaluate_value(index, scope)?),
+            Value::Call(Call { arguments, named, .. }) => {
+                let (arguments, named) = self.evaluate_arguments(arguments, named, scope)?;
+                self.call_object_with_named(object, &arguments, named)
             },
+            _ => unreachable!(),
+        }.map(Some)
+    }
 
-            Value::List(list) => Ok(Object::List(
-                list.items
-                    .into_iter()
-                    .map(|i| self.evaluate_value(i))
-                    .collect::<Result<Vec<_>>>()?,
-            )),
-
-            Value::AssociativeArray(arr) => Ok(Object::AssociativeArray(
-                arr.items
-                    .into_iter()
-                    .map(|(key, value)| {
-                        self.evaluate_value(value).map(|value| {
-                            (
-                                match key {
-                                    Key::Name(str) | Key::String(str) => str,
-                                },
-                                value,
-                            )
-                        })
-                    })
-                    .collect::<Result<HashMap<_, _>>>()?,
-            )),
+    /// Evaluates the items of a list, unpacking spread lists and ranges in their place.
+    fn evaluate_items(&self, items: &[Value], scope: &Scope) -> Result<Vec<Object>> {
+        let mut objects = Vec::with_capacity(items.len());
+
+        for item in items {
+            match item {
+                Value::Spread(Spread { value, .. }) => match self.evaluate_value(value, scope)? {
+                    // `[]` is an empty associative array
+                    Object::AssociativeArray(array) if array.is_empty() => (),
+                    value => objects.extend(value.items()
+                        .ok_or(ManualError::OperationNotValidForType(format!("Cannot spread {} into a list", value.datatype())))?),
+                },
+                item => objects.push(self.evaluate_value(item, scope)?),
+            }
         }
+
+        Ok(objects)
+    }
+
+    /// Evaluates the arguments of a call. Spread associative arrays supply named arguments, which are overridden by
+    /// any given explicitly.
+    fn evaluate_arguments(&self, arguments: &[Value], named: &[(String, Value)], scope: &Scope) -> Result<(Vec<Object>, HashMap<String, Object>)> {
+        let mut positional = Vec::with_capacity(arguments.len());
+        let mut keywords = HashMap::new();
+
+        for argument in arguments {
+            match argument {
+                Value::Spread(Spread { value, .. }) => match self.evaluate_value(value, scope)? {
+                    Object::AssociativeArray(entries) => keywords.extend(entries),
+                    value => positional.extend(value.items()
+                        .ok_or(ManualError::OperationNotValidForType(format!("Cannot spread {} into arguments", value.datatype())))?),
+                },
+                argument => positional.push(self.evaluate_value(argument, scope)?),
+            }
+        }
+
+        for (name, value) in named {
+            keywords.insert(name.clone(), self.evaluate_value(value, scope)?);
+        }
+
+        Ok((positional, keywords))
     }
 
     pub fn evaluate(&self, expression: impl AsRef<str>) -> Result<Object> {
         let ast = self.parse(expression.as_ref())?;
-        Ok(self.evaluate_value(ast)?)
+        Ok(self.evaluate_value(&ast, &Scope::default())?)
     }
 
-    pub fn provider(&self) -> &Provider {
-        self.data_provider.as_ref()
-    }
+    /// # Programs
+    /// Evaluates a [program](crate::parse::ParseContext::parse_program) statement by statement. Bindings and function
+    /// definitions are visible to the statements after them, but never change the context's globals. The result is
+    /// the value of the last statement, or `nothing` if it is a definition.
+    ///
+    /// ```rust
+    /// use expression::eval::Object;
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new());
+    /// let program = r#"
+    ///     let rate = 0.2
+    ///     let tax(price) = price * rate
+    ///     tax(50) + tax(100)
+    /// "#;
+    ///
+    /// assert_eq!(cx.evaluate_program(program).unwrap(), Object::Number(30.0));
+    /// ```
+    pub fn evaluate_program(&self, program: impl AsRef<str>) -> Result<Object> {
+        let program = self.parse_program(program.as_ref())?;
+        let mut scope = Scope::default();
+        let mut result = Object::Nothing;
+
+        for statement in &program.statements {
+            result = match statement {
+                Statement::Binding(pattern, value) => {
+                    let value = self.evaluate_value(value, &scope)?;
+                    let mut bindings = HashMap::new();
+                    self.destructure(pattern, &value, &scope, &mut bindings)?;
+
+                    scope = scope.with(bindings);
+                    Object::Nothing
+                },
+                Statement::Function { name, lambda: Lambda { parameters, body, .. } } => {
+                    let function = self.function(parameters, body, &scope, Some(name));
 
-    pub fn provider_mut(&mut self) -> &mut Provider {
-        self.data_provider.as_mut()
+                    scope = scope.with(HashMap::from([(name.clone(), function)]));
+                    Object::Nothing
+                },
+                Statement::Expression(value) => self.evaluate_value(value, &scope)?,
+            };
+        }
+
+        Ok(result)
     }
+
 }
 
 impl<Provider: DataSource + Clone + 'static> Context<Provider> {
@@ -367,12 +697,101 @@ impl<Provider: DataSource + Clone + 'static> Context<Provider> {
     }
 }
 
+/// The values of the addresses a lambda refers to, looked up when the lambda is created.
+struct Captured(HashMap<String, Object>);
+
+impl DataSource for Captured {
+    fn query(&self, query: impl AsRef<str>) -> Option<Object> {
+        self.0.get(query.as_ref()).cloned()
+    }
+}
+
+/// The function for a lambda, evaluated in the environment captured when it was created. A function given a `name` is
+/// bound to it within its own body, so definitions such as `let fact(n) = n < 2 ? 1 : n * fact(n - 1)` may recurse.
+fn closure(cx: Rc<Context<Captured>>, parameters: Rc<[Pattern]>, body: Rc<Value>, scope: Scope, name: Option<Rc<str>>) -> Object {
+    Object::function_with_named(move |args, mut named| {
+        // Positional arguments fill the parameters in order and named arguments fill the rest. Surplus
+        // positional arguments are ignored, so callbacks only need to declare the parameters they use.
+        let mut args = args.into_iter();
+        let mut bindings = HashMap::new();
+
+        if let Some(name) = &name {
+            bindings.insert(name.to_string(), closure(Rc::clone(&cx), Rc::clone(&parameters), Rc::clone(&body), scope.clone(), Some(Rc::clone(name))));
+        }
+
+        for (index, parameter) in parameters.iter().enumerate() {
+            let argument = args.next().or_else(|| parameter.name().and_then(|name| named.remove(name)));
+
+            if argument.is_none() && !matches!(parameter, Pattern::Default { .. }) {
+                return Err(ManualError::InsufficientOperands(match parameter.name() {
+                    Some(name) => format!("Missing argument `{}`", name),
+                    None => format!("Missing argument {}", index + 1),
+                }).into());
+            }
+
+            cx.destructure_element(parameter, argument.as_ref(), &scope, &mut bindings)?;
+        }
+
+        if let Some(name) = named.keys().next() {
+            return Err(ManualError::UnexpectedArgument(name.clone()).into());
+        }
+
+        cx.evaluate_value(&body, &scope.with(bindings))
+    })
+}
+
+/// Collects the queries of the addresses within `value`, including those in nested lambdas and pattern defaults.
+fn addresses<'a>(value: &'a Value, queries: &mut Vec<&'a str>) {
+    match value {
+        Value::Literal(literal) => if let Literal::Address(address) = &literal.node {
+            queries.push(&address.query);
+        },
+        value => value.children().into_iter().for_each(|value| addresses(value, queries)),
+    }
+}
+
+/// Looks up `key` within `object`, as for both `object.key` and `object[key]`.
+fn member_of(object: Object, key: &Object) -> Result<Object> {
+    match (object, key) {
+        (Object::AssociativeArray(array), Object::String(name)) => array.get(name).cloned().ok_or(ManualError::NoSuchValue(name.clone()).into()),
+        (Object::AssociativeArray(array), Object::Number(index)) => array.get(&format!("{}", index)).cloned().ok_or(ManualError::NoSuchValue(format!("{}", index)).into()),
+
+        (Object::List(list), Object::String(name)) => name
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| list.get(index))
+            .cloned()
+            .ok_or(ManualError::NoSuchValue(name.clone()).into()),
+
+        (Object::List(list), Object::Number(index)) => Some(*index)
+            .filter(|index| index.fract() == 0.0 && *index >= 0.0)
+            .and_then(|index| list.get(index as usize))
+            .cloned()
+            .ok_or(ManualError::NoSuchValue(format!("{}", index)).into()),
+
+        (Object::Range(range), Object::Number(index)) => Some(*index)
+            .filter(|index| index.fract() == 0.0 && *index >= 0.0)
+            .and_then(|index| range.get(index as usize))
+            .map(Object::Number)
+            .ok_or(ManualError::NoSuchValue(format!("{}", index)).into()),
+
+        (Object::String(string), Object::Number(index)) => Some(*index)
+            .filter(|index| index.fract() == 0.0 && *index >= 0.0)
+            .and_then(|index| string.chars().nth(index as usize))
+            .map(|char| Object::String(char.to_string()))
+            .ok_or(ManualError::NoSuchValue(format!("{}", index)).into()),
+
+        (obj @ (Object::List(_) | Object::AssociativeArray(_) | Object::String(_) | Object::Range(_)), key) => Err(ManualError::OperationNotValidForType(format!("Cannot index {} with {}", obj.datatype(), key.datatype())).into()),
+        (obj, _) => Err(ManualError::OperationNotValidForType(format!("Object of type '{}' does not exhibit any accessible members", obj.datatype())).into()),
+    }
+}
+
 impl<Provider: DataSource + Clone> Clone for Context<Provider> {
     fn clone(&self) -> Self {
         Self {
-            globals: self.globals.clone(),
+            globals: Rc::clone(&self.globals),
             data_provider: self.data_provider.clone(),
-            operators: self.operators.clone(),
+            operators: Rc::clone(&self.operators),
         }
     }
 }
@@ -1,16 +1,16 @@
 use alloc::{
     string::String,
-    rc::Rc,
-    boxed::Box,
     borrow::ToOwned,
     format,
     vec,
     vec::Vec,
 };
+use nom::lib::std::collections::HashMap;
 use crate::{
     error::*,
     ManualError,
     Object,
+    parse::template::FormatSpec,
 };
 
 pub(crate) fn to_string(obj: Object) -> Result<String> {
@@ -19,13 +19,9 @@ pub(crate) fn to_string(obj: Object) -> Result<String> {
         Object::Number(number) => format!("{}", number),
         Object::Boolean(boolean) => format!("{}", boolean),
         Object::Nothing => "nothing".to_owned(),
-        Object::List(ls) => format!("{}", ls.iter().cloned().map(to_string)
-            .zip(core::iter::repeat(", ".to_owned()))
-            .map(|(i, j)| i.map(|i| [i, j]))
-            .collect::<Result<Vec<[String; 2]>>>()?
-            .into_iter()
-            .flatten()
-            .collect::<String>()),
+        Object::List(_) | Object::Range(_) => obj.items().unwrap().map(to_string)
+            .collect::<Result<Vec<String>>>()?
+            .join(", "),
         Object::AssociativeArray(ls) => format!("{}", ls.iter()
             .map(|(i, j)| (i.clone(), j.clone()))
             .map(|(key, value)| Ok(format!("\n    {} = {},", key, to_string(value)?)))
@@ -34,106 +30,1033 @@ pub(crate) fn to_string(obj: Object) -> Result<String> {
     })
 }
 
+/// Formats a number according to a template's format specification, so `1234.5` with `,.2` is `1,234.50`.
+pub(crate) fn format_number(number: f64, spec: &FormatSpec) -> String {
+    let number = if spec.percent { number * 100.0 } else { number };
+    let mut text = match spec.precision {
+        Some(precision) => format!("{:.*}", precision.min(FormatSpec::MAX_PRECISION), number),
+        None => format!("{}", number),
+    };
+
+    if spec.thousands {
+        let sign = if text.starts_with('-') { 1 } else { 0 };
+        let end = text.find('.').unwrap_or(text.len());
+
+        // Infinities and NaN have no digits to group
+        if text[sign..end].chars().all(|c| c.is_ascii_digit()) {
+            for position in (sign + 1..end).rev().filter(|position| (end - position) % 3 == 0) {
+                text.insert(position, ',');
+            }
+        }
+    }
+
+    if spec.percent {
+        text.push('%');
+    }
+
+    text
+}
+
 fn global<Func: Fn(Vec<Object>) -> Result<Object> + 'static>(name: impl AsRef<str>, func: Func) -> (String, Object) {
-    (name.as_ref().to_owned(), Object::Function(Rc::new(Box::new(func))))
+    (name.as_ref().to_owned(), Object::function(func))
 }
 
 fn constant(name: impl AsRef<str>, constant: f64) -> (String, Object) {
     (name.as_ref().to_owned(), Object::Number(constant))
 }
 
+/// Reads the arguments of a global in order, so errors can name the function and the position of the argument at fault.
+struct Arguments {
+    function: &'static str,
+    args: vec::IntoIter<Object>,
+    position: usize,
+}
+
+impl Arguments {
+    fn new(function: &'static str, args: Vec<Object>) -> Self {
+        Self {
+            function,
+            args: args.into_iter(),
+            position: 0,
+        }
+    }
+
+    /// The next argument, which may be anything but must be present.
+    fn any(&mut self, expected: &str) -> Result<Object> {
+        self.position += 1;
+        self.args.next().ok_or_else(|| ManualError::InsufficientOperands(format!("{} expects {} as argument {}", self.function, expected, self.position)).into())
+    }
+
+    /// The next argument unless it is missing or `nothing`.
+    fn optional(&mut self) -> Option<Object> {
+        self.position += 1;
+        self.args.next().filter(|arg| !matches!(arg, Object::Nothing))
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        match self.any("a number")? {
+            Object::Number(number) => Ok(number),
+            obj => Err(self.expected("a number", &obj)),
+        }
+    }
+
+    fn optional_number(&mut self) -> Result<Option<f64>> {
+        match self.optional() {
+            None => Ok(None),
+            Some(Object::Number(number)) => Ok(Some(number)),
+            Some(obj) => Err(self.expected("a number", &obj)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.any("a string")? {
+            Object::String(string) => Ok(string),
+            obj => Err(self.expected("a string", &obj)),
+        }
+    }
+
+    fn optional_string(&mut self) -> Result<Option<String>> {
+        match self.optional() {
+            None => Ok(None),
+            Some(Object::String(string)) => Ok(Some(string)),
+            Some(obj) => Err(self.expected("a string", &obj)),
+        }
+    }
+
+    /// A list or range, whose items are produced by [`Object::items`].
+    fn sequence(&mut self) -> Result<Object> {
+        match self.any("a list")? {
+            obj @ (Object::List(_) | Object::Range(_)) => Ok(obj),
+            // `[]` reads as an empty associative array
+            Object::AssociativeArray(array) if array.is_empty() => Ok(Object::List(Vec::new())),
+            obj => Err(self.expected("a list", &obj)),
+        }
+    }
+
+    fn list(&mut self) -> Result<Vec<Object>> {
+        Ok(self.sequence()?.items().unwrap().collect())
+    }
+
+    fn array(&mut self) -> Result<HashMap<String, Object>> {
+        match self.any("an associative array")? {
+            Object::AssociativeArray(array) => Ok(array),
+            obj => Err(self.expected("an associative array", &obj)),
+        }
+    }
+
+    fn function(&mut self) -> Result<Object> {
+        match self.any("a function")? {
+            obj @ Object::Function(_) => Ok(obj),
+            obj => Err(self.expected("a function", &obj)),
+        }
+    }
+
+    /// A number of items to take, skip or group by, which can't be negative.
+    fn count(&mut self) -> Result<usize> {
+        match self.integer()? {
+            count if count < 0.0 => Err(self.expected("a non-negative integer", &Object::Number(count))),
+            count => Ok(count as usize),
+        }
+    }
+
+    /// A number without a fractional part, such as the digits to round to.
+    fn integer(&mut self) -> Result<f64> {
+        match self.number()? {
+            number if number.fract() == 0.0 => Ok(number),
+            number => Err(self.expected("an integer", &Object::Number(number))),
+        }
+    }
+
+    fn optional_integer(&mut self) -> Result<Option<f64>> {
+        match self.optional_number()? {
+            Some(number) if number.fract() != 0.0 => Err(self.expected("an integer", &Object::Number(number))),
+            number => Ok(number),
+        }
+    }
+
+    /// The remaining arguments, all of which must be numbers.
+    fn numbers(mut self) -> Result<Vec<f64>> {
+        let mut numbers = Vec::new();
+
+        while self.args.len() > 0 {
+            numbers.push(self.number()?);
+        }
+
+        Ok(numbers)
+    }
+
+    /// The numbers within the next argument, which is a number or a list or range of them. Nested lists are flattened
+    /// and `nothing` is skipped, as an empty cell would be.
+    fn sample(&mut self) -> Result<Vec<f64>> {
+        let mut values = Vec::new();
+        flatten_values(self.any("numbers")?, &mut values);
+
+        values.into_iter()
+            .map(|value| match value {
+                Object::Number(number) => Ok(number),
+                obj => Err(self.expected("numbers", &obj)),
+            })
+            .collect()
+    }
+
+    /// The numbers within all the remaining arguments, as for `sum(1, [2, 3], 4..5)`.
+    fn data(mut self) -> Result<Vec<f64>> {
+        let mut numbers = Vec::new();
+
+        while self.args.len() > 0 {
+            numbers.extend(self.sample()?);
+        }
+
+        Ok(numbers)
+    }
+
+    /// The numbers within the remaining arguments, of which there must be at least `minimum`.
+    fn data_of_at_least(self, minimum: usize) -> Result<Vec<f64>> {
+        let function = self.function;
+
+        match self.data()? {
+            numbers if numbers.len() < minimum => Err(ManualError::InsufficientOperands(format!("{} expects at least {} number{}, found {}", function, minimum, if minimum == 1 { "" } else { "s" }, numbers.len())).into()),
+            numbers => Ok(numbers),
+        }
+    }
+
+    fn expected(&self, expected: &str, found: &Object) -> Error {
+        ManualError::ExpectedType(format!("{} expects {} as argument {}, found {}", self.function, expected, self.position, match found {
+            Object::Number(number) => format!("{}", number),
+            obj => obj.datatype().to_owned(),
+        })).into()
+    }
+}
+
+/// The most characters `repeat`, `padStart` and `padEnd` will produce, so a mistaken count is an error rather than
+/// exhausting memory.
+const MAX_STRING_LENGTH: usize = 1 << 24;
+
+/// Resolves an index into a sequence of `len` items, counting from the end when negative, as `slice` does.
+fn relative_index(index: f64, len: usize) -> usize {
+    match index {
+        index if index < 0.0 => (len as f64 + index).max(0.0) as usize,
+        index => (index as usize).min(len),
+    }
+}
+
+/// The entries of an associative array ordered by key, so results don't depend on the order of the hash map.
+fn sorted_entries(array: HashMap<String, Object>) -> Vec<(String, Object)> {
+    let mut entries = array.into_iter().collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    entries
+}
+
+/// Merges `other` into `array`, combining associative arrays found under the same key rather than replacing them.
+fn merge(array: &mut HashMap<String, Object>, other: HashMap<String, Object>) {
+    for (key, value) in other {
+        match (array.get_mut(&key), value) {
+            (Some(Object::AssociativeArray(existing)), Object::AssociativeArray(value)) => merge(existing, value),
+            (_, value) => {
+                array.insert(key, value);
+            },
+        }
+    }
+}
+
+/// Collects the items of `obj` and any lists or ranges within it, leaving out `nothing`.
+fn flatten_values(obj: Object, values: &mut Vec<Object>) {
+    match obj {
+        Object::List(_) | Object::Range(_) => obj.items().unwrap().for_each(|item| flatten_values(item, values)),
+        // `[]` reads as an empty associative array
+        Object::AssociativeArray(ref array) if array.is_empty() => {},
+        Object::Nothing => {},
+        obj => values.push(obj),
+    }
+}
+
+fn mean(numbers: &[f64]) -> f64 {
+    numbers.iter().sum::<f64>() / numbers.len() as f64
+}
+
+/// The sum of the products of each pair's deviations from the means, divided by `n - correction`. A correction of 1
+/// estimates the covariance of a population from a sample of it.
+fn covariance(xs: &[f64], ys: &[f64], correction: usize) -> f64 {
+    let (x_mean, y_mean) = (mean(xs), mean(ys));
+
+    xs.iter().zip(ys)
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum::<f64>() / (xs.len() - correction) as f64
+}
+
+/// The value below which the fraction `q` of the `sorted` numbers lie, interpolating between the nearest two.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+
+    lower + (upper - lower) * rank.fract()
+}
+
+/// Calls a function passed to a global with positional arguments.
+fn call(function: &Object, arguments: Vec<Object>) -> Result<Object> {
+    function.call(arguments, HashMap::new())
+}
+
+/// Calls a predicate passed to the global `function`, which must answer with a boolean.
+fn test(function: &'static str, predicate: &Object, arguments: Vec<Object>) -> Result<bool> {
+    match call(predicate, arguments)? {
+        Object::Boolean(result) => Ok(result),
+        obj => Err(ManualError::ExpectedType(format!("{} expects its function to return a boolean, found {}", function, obj.datatype())).into()),
+    }
+}
+
+/// Greatest common divisor of two integers by Euclid's algorithm.
+fn gcd(a: f64, b: f64) -> f64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+
+    while b != 0.0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+#[allow(non_upper_case_globals)]
 mod globals {
     use alloc::borrow::ToOwned;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
     use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use core::cmp::Ordering;
+    use nom::lib::std::collections::HashMap;
     use crate::{ManualError, Object, error::*};
+    use super::{call, flatten_values, mean, relative_index, sorted_entries, test, Arguments, MAX_STRING_LENGTH};
 
     type Global = fn(Vec<Object>) -> Result<Object>;
 
-    macro_rules! glob {
-        ($($arg:ident),* => $body:expr) => {
-            |args| {
-                let mut args = args.into_iter();
-                $(let $arg = args.next();)*
-
-                $body
-            }
+    /// A function of a single number.
+    macro_rules! unary {
+        ($name:literal, $func:expr) => {
+            |args| Ok(Object::Number($func(Arguments::new($name, args).number()?)))
         };
     }
 
-    pub(super) const to_string: Global = |args| if args.len() != 1 {
-        Ok(Object::String(crate::eval::globals::to_string(args.get(0).unwrap().clone())?))
-    } else {
-        Err(ManualError::InsufficientOperands("toString".to_owned()).into())
+    pub(super) const to_string: Global = |args| match <[Object; 1]>::try_from(args) {
+        Ok([obj]) => Ok(Object::String(crate::eval::globals::to_string(obj)?)),
+        Err(_) => Err(ManualError::InsufficientOperands("toString".to_owned()).into()),
     };
 
     pub(super) const identity: Global = |args| args.get(0)
         .ok_or(ManualError::InsufficientOperands("identity".to_owned()).into())
         .cloned();
 
-    pub(super) const sin: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.sin())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const cos: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.cos())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const tan: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.tan())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const sinh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.sinh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const cosh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.cosh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const tanh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.tanh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const asin: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.asin())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const acos: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.acos())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const atan: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.atan())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const asinh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.asinh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const acosh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.acosh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const atanh: Global = glob!(x => match x {
-        Some(Object::Number(x)) => Ok(Object::Number(x.atanh())),
-        _ => Err(ManualError::ExpectedType("Number".to_owned()).into())
-    });
-
-    pub(super) const atan2: Global = glob!(x, y => match (x, y) {
-        (Some(Object::Number(x)), Some(Object::Number(y))) => Ok(Object::Number(x.atan2(y))),
-        _ => Err(ManualError::ExpectedType("Number, Number".to_owned()).into())
-    });
+    pub(super) const sin: Global = unary!("sin", f64::sin);
+    pub(super) const cos: Global = unary!("cos", f64::cos);
+    pub(super) const tan: Global = unary!("tan", f64::tan);
+    pub(super) const sinh: Global = unary!("sinh", f64::sinh);
+    pub(super) const cosh: Global = unary!("cosh", f64::cosh);
+    pub(super) const tanh: Global = unary!("tanh", f64::tanh);
+    pub(super) const asin: Global = unary!("asin", f64::asin);
+    pub(super) const acos: Global = unary!("acos", f64::acos);
+    pub(super) const atan: Global = unary!("atan", f64::atan);
+    pub(super) const asinh: Global = unary!("asinh", f64::asinh);
+    pub(super) const acosh: Global = unary!("acosh", f64::acosh);
+    pub(super) const atanh: Global = unary!("atanh", f64::atanh);
+
+    pub(super) const atan2: Global = |args| {
+        let mut args = Arguments::new("atan2", args);
+        Ok(Object::Number(args.number()?.atan2(args.number()?)))
+    };
+
+    pub(super) const abs: Global = unary!("abs", f64::abs);
+    pub(super) const sqrt: Global = unary!("sqrt", f64::sqrt);
+    pub(super) const ln: Global = unary!("ln", f64::ln);
+    pub(super) const exp: Global = unary!("exp", f64::exp);
+    pub(super) const floor: Global = unary!("floor", f64::floor);
+    pub(super) const ceil: Global = unary!("ceil", f64::ceil);
+    pub(super) const trunc: Global = unary!("trunc", f64::trunc);
+
+    /// `-1`, `0` or `1`, unlike `f64::signum` which gives `0` a sign.
+    pub(super) const sign: Global = unary!("sign", |x: f64| if x == 0.0 { 0.0 } else { x.signum() });
+
+    /// log(x, base ?? 10)
+    pub(super) const log: Global = |args| {
+        let mut args = Arguments::new("log", args);
+        let x = args.number()?;
+
+        Ok(Object::Number(match args.optional_number()? {
+            Some(base) => x.log(base),
+            None => x.log10(),
+        }))
+    };
+
+    /// round(x, digits ?? 0), rounding halves away from zero. Negative digits round to tens, hundreds and so on.
+    pub(super) const round: Global = |args| {
+        let mut args = Arguments::new("round", args);
+        let x = args.number()?;
+        let scale = 10f64.powf(args.optional_integer()?.unwrap_or(0.0));
+
+        Ok(Object::Number((x * scale).round() / scale))
+    };
+
+    pub(super) const min: Global = |args| Arguments::new("min", args).numbers()?
+        .into_iter()
+        .reduce(f64::min)
+        .map(Object::Number)
+        .ok_or(ManualError::InsufficientOperands("min expects a number as argument 1".to_owned()).into());
+
+    pub(super) const max: Global = |args| Arguments::new("max", args).numbers()?
+        .into_iter()
+        .reduce(f64::max)
+        .map(Object::Number)
+        .ok_or(ManualError::InsufficientOperands("max expects a number as argument 1".to_owned()).into());
+
+    /// clamp(x, low, high)
+    pub(super) const clamp: Global = |args| {
+        let mut args = Arguments::new("clamp", args);
+        let (x, low, high) = (args.number()?, args.number()?, args.number()?);
+
+        if low > high {
+            return Err(ManualError::OperationNotValidForType(format!("clamp expects its lower bound {} not to exceed its upper bound {}", low, high)).into());
+        }
+
+        Ok(Object::Number(x.max(low).min(high)))
+    };
+
+    /// The length of the vector with the given components, such as the hypotenuse of a right triangle.
+    pub(super) const hypot: Global = |args| Ok(Object::Number(Arguments::new("hypot", args).numbers()?
+        .into_iter()
+        .fold(0.0, f64::hypot)));
+
+    pub(super) const gcd: Global = |args| {
+        let mut args = Arguments::new("gcd", args);
+        Ok(Object::Number(super::gcd(args.integer()?, args.integer()?)))
+    };
+
+    pub(super) const lcm: Global = |args| {
+        let mut args = Arguments::new("lcm", args);
+        let (a, b) = (args.integer()?, args.integer()?);
+
+        Ok(Object::Number(match super::gcd(a, b) {
+            0.0 => 0.0,
+            divisor => (a / divisor * b).abs(),
+        }))
+    };
+
+    /// Grows past the largest representable number to infinity for arguments above 170.
+    pub(super) const factorial: Global = |args| {
+        let mut args = Arguments::new("factorial", args);
+
+        match args.integer()? {
+            n if n < 0.0 => Err(args.expected("a non-negative integer", &Object::Number(n))),
+            n => Ok(Object::Number((2..=n.min(171.0) as u64).fold(1.0, |product, i| product * i as f64))),
+        }
+    };
+
+    /// The number of characters in a string, or of items in a list, range or associative array.
+    pub(super) const len: Global = |args| {
+        let mut args = Arguments::new("len", args);
+
+        Ok(Object::Number(match args.any("a string or list")? {
+            Object::String(string) => string.chars().count(),
+            Object::List(list) => list.len(),
+            Object::Range(range) => range.len(),
+            Object::AssociativeArray(array) => array.len(),
+            obj => return Err(args.expected("a string or list", &obj)),
+        } as f64))
+    };
+
+    pub(super) const upper: Global = |args| Ok(Object::String(Arguments::new("upper", args).string()?.to_uppercase()));
+    pub(super) const lower: Global = |args| Ok(Object::String(Arguments::new("lower", args).string()?.to_lowercase()));
+    pub(super) const trim: Global = |args| Ok(Object::String(Arguments::new("trim", args).string()?.trim().to_owned()));
+
+    /// split(string, separator ?? ''), where an empty separator splits the string into characters.
+    pub(super) const split: Global = |args| {
+        let mut args = Arguments::new("split", args);
+        let (string, separator) = (args.string()?, args.optional_string()?.unwrap_or_default());
+
+        Ok(Object::List(match separator.as_str() {
+            "" => string.chars().map(|char| Object::String(char.to_string())).collect(),
+            separator => string.split(separator).map(|part| Object::String(part.to_owned())).collect(),
+        }))
+    };
+
+    /// join(list, separator ?? ''), converting items which aren't strings as `toString` does.
+    pub(super) const join: Global = |args| {
+        let mut args = Arguments::new("join", args);
+        let items = args.sequence()?.items().unwrap()
+            .map(crate::eval::globals::to_string)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Object::String(items.join(&args.optional_string()?.unwrap_or_default())))
+    };
+
+    /// replace(string, pattern, replacement), replacing every occurrence.
+    pub(super) const replace: Global = |args| {
+        let mut args = Arguments::new("replace", args);
+        let (string, pattern, replacement) = (args.string()?, args.string()?, args.string()?);
+
+        Ok(Object::String(string.replace(&pattern, &replacement)))
+    };
+
+    pub(super) const contains: Global = |args| {
+        let mut args = Arguments::new("contains", args);
+        Ok(Object::Boolean(args.string()?.contains(&args.string()?)))
+    };
+
+    pub(super) const starts_with: Global = |args| {
+        let mut args = Arguments::new("startsWith", args);
+        Ok(Object::Boolean(args.string()?.starts_with(&args.string()?)))
+    };
+
+    pub(super) const ends_with: Global = |args| {
+        let mut args = Arguments::new("endsWith", args);
+        Ok(Object::Boolean(args.string()?.ends_with(&args.string()?)))
+    };
+
+    /// substring(string, start, end ?? len(string)), counting characters. Indices are clamped to the string and may be
+    /// given in either order.
+    pub(super) const substring: Global = |args| {
+        let mut args = Arguments::new("substring", args);
+        let chars = args.string()?.chars().collect::<Vec<_>>();
+        let start = args.integer()?.clamp(0.0, chars.len() as f64) as usize;
+        let end = args.optional_integer()?.map_or(chars.len(), |end| end.clamp(0.0, chars.len() as f64) as usize);
+
+        Ok(Object::String(chars[start.min(end)..start.max(end)].iter().collect()))
+    };
+
+    /// slice(string, start, end ?? len(string)), counting characters from the end of the string when negative.
+    pub(super) const slice: Global = |args| {
+        let mut args = Arguments::new("slice", args);
+        let chars = args.string()?.chars().collect::<Vec<_>>();
+        let start = relative_index(args.integer()?, chars.len());
+        let end = args.optional_integer()?.map_or(chars.len(), |end| relative_index(end, chars.len()));
+
+        Ok(Object::String(chars[start.min(end)..end].iter().collect()))
+    };
+
+    /// The padding needed to bring `string` to `length` characters by repeating `fill`, which defaults to a space.
+    fn padding(function: &'static str, args: Vec<Object>) -> Result<(String, String)> {
+        let mut args = Arguments::new(function, args);
+        let (string, length) = (args.string()?, args.integer()?);
+
+        if length > MAX_STRING_LENGTH as f64 {
+            return Err(args.expected(&format!("a length of at most {}", MAX_STRING_LENGTH), &Object::Number(length)));
+        }
+
+        let fill = args.optional_string()?.unwrap_or(" ".to_owned());
+        let missing = (length as usize).saturating_sub(string.chars().count());
+
+        Ok((fill.chars().cycle().take(if fill.is_empty() { 0 } else { missing }).collect(), string))
+    }
+
+    pub(super) const pad_start: Global = |args| {
+        let (padding, string) = padding("padStart", args)?;
+        Ok(Object::String(padding + &string))
+    };
+
+    pub(super) const pad_end: Global = |args| {
+        let (padding, string) = padding("padEnd", args)?;
+        Ok(Object::String(string + &padding))
+    };
+
+    pub(super) const repeat: Global = |args| {
+        let mut args = Arguments::new("repeat", args);
+        let (string, times) = (args.string()?, args.count()?);
+
+        match string.chars().count() {
+            length if length > 0 && times > MAX_STRING_LENGTH / length => Err(args.expected(&format!("a count of at most {}", MAX_STRING_LENGTH / length), &Object::Number(times as f64))),
+            _ => Ok(Object::String(string.repeat(times))),
+        }
+    };
+
+    /// The position in characters of the first occurrence of `pattern`, or `-1` if there is none.
+    pub(super) const index_of: Global = |args| {
+        let mut args = Arguments::new("indexOf", args);
+        let (string, pattern) = (args.string()?, args.string()?);
+
+        Ok(Object::Number(string.find(&pattern)
+            .map_or(-1.0, |index| string[..index].chars().count() as f64)))
+    };
+
+    /// map(list, f), calling `f(item, index)` for each item.
+    pub(super) const map: Global = |args| {
+        let mut args = Arguments::new("map", args);
+        let (list, function) = (args.sequence()?, args.function()?);
+
+        list.items().unwrap()
+            .enumerate()
+            .map(|(index, item)| call(&function, vec![item, Object::Number(index as f64)]))
+            .collect::<Result<Vec<_>>>()
+            .map(Object::List)
+    };
+
+    /// filter(list, predicate), keeping the items for which `predicate(item, index)` is true.
+    pub(super) const filter: Global = |args| {
+        let mut args = Arguments::new("filter", args);
+        let (list, predicate) = (args.sequence()?, args.function()?);
+        let mut kept = Vec::new();
+
+        for (index, item) in list.items().unwrap().enumerate() {
+            if test("filter", &predicate, vec![item.clone(), Object::Number(index as f64)])? {
+                kept.push(item);
+            }
+        }
+
+        Ok(Object::List(kept))
+    };
+
+    /// reduce(list, f, initial ?? first item), calling `f(accumulator, item, index)` for each item. Also known as `fold`.
+    pub(super) const reduce: Global = |args| {
+        let mut args = Arguments::new("reduce", args);
+        let (list, function, initial) = (args.sequence()?, args.function()?, args.optional());
+        let mut items = list.items().unwrap().enumerate();
+
+        let initial = match initial.or_else(|| items.next().map(|(_, item)| item)) {
+            Some(initial) => initial,
+            None => return Err(ManualError::InsufficientOperands("reduce expects an initial value for an empty list".to_owned()).into()),
+        };
+
+        items.try_fold(initial, |accumulator, (index, item)| call(&function, vec![accumulator, item, Object::Number(index as f64)]))
+    };
+
+    /// any(list, predicate), stopping at the first item for which `predicate(item, index)` is true.
+    pub(super) const any: Global = |args| {
+        let mut args = Arguments::new("any", args);
+        let (list, predicate) = (args.sequence()?, args.function()?);
+
+        for (index, item) in list.items().unwrap().enumerate() {
+            if test("any", &predicate, vec![item, Object::Number(index as f64)])? {
+                return Ok(Object::Boolean(true));
+            }
+        }
+
+        Ok(Object::Boolean(false))
+    };
+
+    /// all(list, predicate), stopping at the first item for which `predicate(item, index)` is false.
+    pub(super) const all: Global = |args| {
+        let mut args = Arguments::new("all", args);
+        let (list, predicate) = (args.sequence()?, args.function()?);
+
+        for (index, item) in list.items().unwrap().enumerate() {
+            if !test("all", &predicate, vec![item, Object::Number(index as f64)])? {
+                return Ok(Object::Boolean(false));
+            }
+        }
+
+        Ok(Object::Boolean(true))
+    };
+
+    /// find(list, predicate), the first item for which `predicate(item, index)` is true, or `nothing`.
+    pub(super) const find: Global = |args| {
+        let mut args = Arguments::new("find", args);
+        let (list, predicate) = (args.sequence()?, args.function()?);
+
+        for (index, item) in list.items().unwrap().enumerate() {
+            if test("find", &predicate, vec![item.clone(), Object::Number(index as f64)])? {
+                return Ok(item);
+            }
+        }
+
+        Ok(Object::Nothing)
+    };
+
+    /// sortBy(list, compare), where `compare(a, b)` is negative when `a` comes first, positive when `b` does and zero
+    /// when their order doesn't matter. Items which compare equal keep their order.
+    pub(super) const sort_by: Global = |args| {
+        let mut args = Arguments::new("sortBy", args);
+        let (mut list, compare) = (args.list()?, args.function()?);
+
+        // The sort can't be interrupted, so the first error is kept and the remaining comparisons skipped
+        let error = RefCell::new(None);
+
+        list.sort_by(|a, b| {
+            if error.borrow().is_some() {
+                return Ordering::Equal;
+            }
+
+            match call(&compare, vec![a.clone(), b.clone()]) {
+                Ok(Object::Number(order)) => order.partial_cmp(&0.0).unwrap_or(Ordering::Equal),
+                Ok(obj) => {
+                    *error.borrow_mut() = Some(ManualError::ExpectedType(format!("sortBy expects its function to return a number, found {}", obj.datatype())).into());
+                    Ordering::Equal
+                },
+                Err(err) => {
+                    *error.borrow_mut() = Some(err);
+                    Ordering::Equal
+                },
+            }
+        });
+
+        match error.into_inner() {
+            Some(err) => Err(err),
+            None => Ok(Object::List(list)),
+        }
+    };
+
+    pub(super) const reverse: Global = |args| {
+        let mut list = Arguments::new("reverse", args).list()?;
+        list.reverse();
+
+        Ok(Object::List(list))
+    };
+
+    /// The items of a list without repeats, in the order they first appear.
+    pub(super) const unique: Global = |args| {
+        let mut distinct = Vec::new();
+
+        for item in Arguments::new("unique", args).list()? {
+            if !distinct.contains(&item) {
+                distinct.push(item);
+            }
+        }
+
+        Ok(Object::List(distinct))
+    };
+
+    /// flatten(list, depth ?? 1), replacing lists within the list by their items.
+    pub(super) const flatten: Global = |args| {
+        fn flatten(items: impl Iterator<Item = Object>, depth: usize, flattened: &mut Vec<Object>) {
+            for item in items {
+                match item.items() {
+                    Some(items) if depth > 0 => flatten(items, depth - 1, flattened),
+                    _ => flattened.push(item.clone()),
+                }
+            }
+        }
+
+        let mut args = Arguments::new("flatten", args);
+        let list = args.sequence()?;
+        let depth = match args.optional_integer()? {
+            Some(depth) if depth < 0.0 => return Err(args.expected("a non-negative integer", &Object::Number(depth))),
+            depth => depth.unwrap_or(1.0) as usize,
+        };
+
+        let mut flattened = Vec::new();
+        flatten(list.items().unwrap(), depth, &mut flattened);
+
+        Ok(Object::List(flattened))
+    };
+
+    /// zip(a, b, ...), pairing up the items of each list until the shortest runs out.
+    pub(super) const zip: Global = |args| {
+        let mut args = Arguments::new("zip", args);
+        let mut lists = Vec::new();
+
+        while args.args.len() > 0 {
+            lists.push(args.list()?.into_iter());
+        }
+
+        let mut zipped = Vec::new();
+
+        while let Some(items) = lists.iter_mut().map(Iterator::next).collect::<Option<Vec<_>>>().filter(|items| !items.is_empty()) {
+            zipped.push(Object::List(items));
+        }
+
+        Ok(Object::List(zipped))
+    };
+
+    /// Pairs each item with its index, as `[index, item]`.
+    pub(super) const enumerate: Global = |args| Ok(Object::List(Arguments::new("enumerate", args).sequence()?
+        .items().unwrap()
+        .enumerate()
+        .map(|(index, item)| Object::List(vec![Object::Number(index as f64), item]))
+        .collect()));
+
+    /// take(list, count), the first `count` items.
+    pub(super) const take: Global = |args| {
+        let mut args = Arguments::new("take", args);
+        let (list, n) = (args.sequence()?, args.count()?);
+
+        Ok(Object::List(list.items().unwrap().take(n).collect()))
+    };
+
+    /// skip(list, count), all but the first `count` items.
+    pub(super) const skip: Global = |args| {
+        let mut args = Arguments::new("skip", args);
+        let (list, n) = (args.sequence()?, args.count()?);
+
+        Ok(Object::List(list.items().unwrap().skip(n).collect()))
+    };
+
+    /// chunk(list, size), splitting the list into lists of `size` items. The last may be shorter.
+    pub(super) const chunk: Global = |args| {
+        let mut args = Arguments::new("chunk", args);
+        let list = args.list()?;
+
+        match args.count()? {
+            0 => Err(args.expected("a positive integer", &Object::Number(0.0))),
+            size => Ok(Object::List(list.chunks(size).map(|items| Object::List(items.to_vec())).collect())),
+        }
+    };
+
+    /// groupBy(list, key), collecting items into lists under the text of `key(item, index)`.
+    pub(super) const group_by: Global = |args| {
+        let mut args = Arguments::new("groupBy", args);
+        let (list, key) = (args.sequence()?, args.function()?);
+        let mut groups = HashMap::<String, Vec<Object>>::new();
+
+        for (index, item) in list.items().unwrap().enumerate() {
+            let key = crate::eval::globals::to_string(call(&key, vec![item.clone(), Object::Number(index as f64)])?)?;
+            groups.entry(key).or_default().push(item);
+        }
+
+        Ok(Object::AssociativeArray(groups.into_iter().map(|(key, group)| (key, Object::List(group))).collect()))
+    };
+
+    /// The keys of an associative array in alphabetical order.
+    pub(super) const keys: Global = |args| Ok(Object::List(sorted_entries(Arguments::new("keys", args).array()?)
+        .into_iter()
+        .map(|(key, _)| Object::String(key))
+        .collect()));
+
+    /// The values of an associative array, ordered by their keys.
+    pub(super) const values: Global = |args| Ok(Object::List(sorted_entries(Arguments::new("values", args).array()?)
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect()));
+
+    /// The entries of an associative array as `[key, value]` pairs, ordered by key.
+    pub(super) const entries: Global = |args| Ok(Object::List(sorted_entries(Arguments::new("entries", args).array()?)
+        .into_iter()
+        .map(|(key, value)| Object::List(vec![Object::String(key), value]))
+        .collect()));
+
+    /// Builds an associative array from a list of `[key, value]` pairs. Later pairs replace earlier ones with the
+    /// same key.
+    pub(super) const from_entries: Global = |args| {
+        let mut args = Arguments::new("fromEntries", args);
+
+        args.list()?
+            .into_iter()
+            .map(|entry| match entry {
+                Object::List(pair) => match <[Object; 2]>::try_from(pair) {
+                    Ok([Object::String(key), value]) => Ok((key, value)),
+                    Ok(pair) => Err(args.expected("a list of [key, value] pairs", &Object::List(pair.into()))),
+                    Err(pair) => Err(args.expected("a list of [key, value] pairs", &Object::List(pair))),
+                },
+                obj => Err(args.expected("a list of [key, value] pairs", &obj)),
+            })
+            .collect::<Result<_>>()
+            .map(Object::AssociativeArray)
+    };
+
+    pub(super) const has: Global = |args| {
+        let mut args = Arguments::new("has", args);
+        Ok(Object::Boolean(args.array()?.contains_key(&args.string()?)))
+    };
+
+    /// get(array, key, default ?? nothing), which unlike `array.key` isn't an error when the key is missing.
+    pub(super) const get: Global = |args| {
+        let mut args = Arguments::new("get", args);
+        let (mut array, key) = (args.array()?, args.string()?);
+
+        Ok(array.remove(&key)
+            .or_else(|| args.optional())
+            .unwrap_or(Object::Nothing))
+    };
+
+    /// set(array, key, value), a copy of the array with the key set to the value.
+    pub(super) const set: Global = |args| {
+        let mut args = Arguments::new("set", args);
+        let (mut array, key, value) = (args.array()?, args.string()?, args.any("a value")?);
+        array.insert(key, value);
+
+        Ok(Object::AssociativeArray(array))
+    };
+
+    /// without(array, key, ...), a copy of the array without the given keys.
+    pub(super) const without: Global = |args| {
+        let mut args = Arguments::new("without", args);
+        let mut array = args.array()?;
+
+        while args.args.len() > 0 {
+            array.remove(&args.string()?);
+        }
+
+        Ok(Object::AssociativeArray(array))
+    };
+
+    /// mapValues(array, f), replacing each value by `f(value, key)`.
+    pub(super) const map_values: Global = |args| {
+        let mut args = Arguments::new("mapValues", args);
+        let (array, function) = (args.array()?, args.function()?);
+
+        array.into_iter()
+            .map(|(key, value)| Ok((key.clone(), call(&function, vec![value, Object::String(key)])?)))
+            .collect::<Result<_>>()
+            .map(Object::AssociativeArray)
+    };
+
+    /// filterEntries(array, predicate), keeping the entries for which `predicate(value, key)` is true.
+    pub(super) const filter_entries: Global = |args| {
+        let mut args = Arguments::new("filterEntries", args);
+        let (array, predicate) = (args.array()?, args.function()?);
+        let mut kept = HashMap::new();
+
+        for (key, value) in array {
+            if test("filterEntries", &predicate, vec![value.clone(), Object::String(key.clone())])? {
+                kept.insert(key, value);
+            }
+        }
+
+        Ok(Object::AssociativeArray(kept))
+    };
+
+    /// merge(a, b, ...), combining associative arrays from left to right. Unlike `a + b`, arrays nested under the same
+    /// key are merged too, rather than the later one replacing the earlier.
+    pub(super) const merge: Global = |args| {
+        let mut args = Arguments::new("merge", args);
+        let mut merged = HashMap::new();
+
+        while args.args.len() > 0 {
+            super::merge(&mut merged, args.array()?);
+        }
+
+        Ok(Object::AssociativeArray(merged))
+    };
+
+    /// sum(numbers...), which is `0` when there are none.
+    pub(super) const sum: Global = |args| Ok(Object::Number(Arguments::new("sum", args).data()?.into_iter().sum()));
+
+    /// The arithmetic mean. Also known as `mean`.
+    pub(super) const avg: Global = |args| Ok(Object::Number(mean(&Arguments::new("avg", args).data_of_at_least(1)?)));
+
+    /// The middle number, or the mean of the middle two when there is an even number of them.
+    pub(super) const median: Global = |args| {
+        let mut numbers = Arguments::new("median", args).data_of_at_least(1)?;
+        numbers.sort_by(f64::total_cmp);
+
+        Ok(Object::Number(super::quantile(&numbers, 0.5)))
+    };
+
+    /// The most frequent number. Ties go to whichever appears first.
+    pub(super) const mode: Global = |args| {
+        let numbers = Arguments::new("mode", args).data_of_at_least(1)?;
+        let frequency = |number: &f64| numbers.iter().filter(|other| *other == number).count();
+
+        Ok(Object::Number(numbers.iter()
+            .fold((numbers[0], 0), |(most_frequent, highest), number| match frequency(number) {
+                frequency if frequency > highest => (*number, frequency),
+                _ => (most_frequent, highest),
+            })
+            .0))
+    };
+
+    /// The variance of a sample. `varianceP` gives the variance of a whole population.
+    pub(super) const variance: Global = |args| {
+        let numbers = Arguments::new("variance", args).data_of_at_least(2)?;
+        Ok(Object::Number(super::covariance(&numbers, &numbers, 1)))
+    };
+
+    pub(super) const variance_p: Global = |args| {
+        let numbers = Arguments::new("varianceP", args).data_of_at_least(1)?;
+        Ok(Object::Number(super::covariance(&numbers, &numbers, 0)))
+    };
+
+    /// The standard deviation of a sample. `stdevP` gives the standard deviation of a whole population.
+    pub(super) const stdev: Global = |args| {
+        let numbers = Arguments::new("stdev", args).data_of_at_least(2)?;
+        Ok(Object::Number(super::covariance(&numbers, &numbers, 1).sqrt()))
+    };
+
+    pub(super) const stdev_p: Global = |args| {
+        let numbers = Arguments::new("stdevP", args).data_of_at_least(1)?;
+        Ok(Object::Number(super::covariance(&numbers, &numbers, 0).sqrt()))
+    };
+
+    /// Reads the numbers and the fraction of them to find for `quantile` and `percentile`, of which the latter counts
+    /// in percent.
+    fn quantile_of(function: &'static str, args: Vec<Object>, scale: f64) -> Result<Object> {
+        let mut args = Arguments::new(function, args);
+        let mut numbers = args.sample()?;
+
+        let q = match args.number()? {
+            q if (0.0..=scale).contains(&q) => q / scale,
+            q => return Err(args.expected(&format!("a number between 0 and {}", scale), &Object::Number(q))),
+        };
+
+        if numbers.is_empty() {
+            return Err(ManualError::InsufficientOperands(format!("{} expects at least 1 number, found 0", function)).into());
+        }
+
+        numbers.sort_by(f64::total_cmp);
+        Ok(Object::Number(super::quantile(&numbers, q)))
+    }
+
+    /// quantile(numbers, q), the value below which the fraction `q` of the numbers lie.
+    pub(super) const quantile: Global = |args| quantile_of("quantile", args, 1.0);
+
+    /// percentile(numbers, p), the value below which `p` percent of the numbers lie.
+    pub(super) const percentile: Global = |args| quantile_of("percentile", args, 100.0);
+
+    /// The number of values other than `nothing`, of any type. Ranges are counted without producing their items.
+    pub(super) const count: Global = |args| {
+        fn count_values(obj: &Object) -> usize {
+            match obj {
+                Object::Range(range) => range.len(),
+                Object::List(list) => list.iter().map(count_values).fold(0, usize::saturating_add),
+                Object::Nothing => 0,
+                // `[]` reads as an empty associative array
+                Object::AssociativeArray(array) if array.is_empty() => 0,
+                _ => 1,
+            }
+        }
+
+        Ok(Object::Number(args.iter().map(count_values).fold(0, usize::saturating_add) as f64))
+    };
+
+    /// countIf(values, predicate), the number of values other than `nothing` for which `predicate(value)` is true.
+    pub(super) const count_if: Global = |args| {
+        let mut args = Arguments::new("countIf", args);
+        let (list, predicate) = (args.sequence()?, args.function()?);
+
+        let mut items = Vec::new();
+        flatten_values(list, &mut items);
+
+        items.into_iter().try_fold(0.0, |total, value| Ok(match test("countIf", &predicate, vec![value])? {
+            true => total + 1.0,
+            false => total,
+        })).map(Object::Number)
+    };
+
+    /// Reads two lists of numbers which are paired up item by item. Pairs where either is `nothing` are left out.
+    fn pairs(function: &'static str, args: Vec<Object>) -> Result<(Vec<f64>, Vec<f64>)> {
+        let mut args = Arguments::new(function, args);
+        let (xs, ys) = (args.list()?, args.list()?);
+
+        if xs.len() != ys.len() {
+            return Err(ManualError::OperationNotValidForType(format!("{} expects lists of the same length, found {} and {}", function, xs.len(), ys.len())).into());
+        }
+
+        let (xs, ys) = xs.into_iter().zip(ys)
+            .filter(|pair| !matches!(pair, (Object::Nothing, _) | (_, Object::Nothing)))
+            .map(|pair| match pair {
+                (Object::Number(x), Object::Number(y)) => Ok((x, y)),
+                (Object::Number(_), obj) | (obj, _) => Err(ManualError::ExpectedType(format!("{} expects lists of numbers, found {}", function, obj.datatype())).into()),
+            })
+            .collect::<Result<(Vec<_>, Vec<_>)>>()?;
+
+        match xs.len() {
+            0 | 1 => Err(ManualError::InsufficientOperands(format!("{} expects at least 2 pairs of numbers, found {}", function, xs.len())).into()),
+            _ => Ok((xs, ys)),
+        }
+    }
+
+    /// covariance(xs, ys), the covariance of paired samples.
+    pub(super) const covariance: Global = |args| {
+        let (xs, ys) = pairs("covariance", args)?;
+        Ok(Object::Number(super::covariance(&xs, &ys, 1)))
+    };
+
+    /// correl(xs, ys), the Pearson correlation coefficient of paired samples, between `-1` and `1`.
+    pub(super) const correl: Global = |args| {
+        let (xs, ys) = pairs("correl", args)?;
+
+        match (super::covariance(&xs, &xs, 1), super::covariance(&ys, &ys, 1)) {
+            (x_variance, y_variance) if x_variance == 0.0 || y_variance == 0.0 => Err(ManualError::OperationNotValidForType("correl is undefined when either list holds a single repeated number".to_owned()).into()),
+            (x_variance, y_variance) => Ok(Object::Number(super::covariance(&xs, &ys, 1) / (x_variance * y_variance).sqrt())),
+        }
+    };
 }
 
 pub(crate) fn get_standard_globals() -> Vec<(String, Object)> {
@@ -155,6 +1078,87 @@ pub(crate) fn get_standard_globals() -> Vec<(String, Object)> {
         global("atanh", globals::atanh),
         global("atan2", globals::atan2),
 
+        global("abs", globals::abs),
+        global("sqrt", globals::sqrt),
+        global("ln", globals::ln),
+        global("log", globals::log),
+        global("exp", globals::exp),
+        global("floor", globals::floor),
+        global("ceil", globals::ceil),
+        global("round", globals::round),
+        global("trunc", globals::trunc),
+        global("sign", globals::sign),
+        global("min", globals::min),
+        global("max", globals::max),
+        global("clamp", globals::clamp),
+        global("hypot", globals::hypot),
+        global("gcd", globals::gcd),
+        global("lcm", globals::lcm),
+        global("factorial", globals::factorial),
+
+        global("len", globals::len),
+        global("upper", globals::upper),
+        global("lower", globals::lower),
+        global("trim", globals::trim),
+        global("split", globals::split),
+        global("join", globals::join),
+        global("replace", globals::replace),
+        global("contains", globals::contains),
+        global("startsWith", globals::starts_with),
+        global("endsWith", globals::ends_with),
+        global("substring", globals::substring),
+        global("slice", globals::slice),
+        global("padStart", globals::pad_start),
+        global("padEnd", globals::pad_end),
+        global("repeat", globals::repeat),
+        global("indexOf", globals::index_of),
+
+        global("map", globals::map),
+        global("filter", globals::filter),
+        global("reduce", globals::reduce),
+        global("fold", globals::reduce),
+        global("any", globals::any),
+        global("all", globals::all),
+        global("find", globals::find),
+        global("sortBy", globals::sort_by),
+        global("reverse", globals::reverse),
+        global("unique", globals::unique),
+        global("flatten", globals::flatten),
+        global("zip", globals::zip),
+        global("enumerate", globals::enumerate),
+        global("take", globals::take),
+        global("skip", globals::skip),
+        global("chunk", globals::chunk),
+        global("groupBy", globals::group_by),
+
+        global("keys", globals::keys),
+        global("values", globals::values),
+        global("entries", globals::entries),
+        global("fromEntries", globals::from_entries),
+        global("has", globals::has),
+        global("get", globals::get),
+        global("set", globals::set),
+        global("without", globals::without),
+        global("mapValues", globals::map_values),
+        global("filterEntries", globals::filter_entries),
+        global("merge", globals::merge),
+
+        global("sum", globals::sum),
+        global("avg", globals::avg),
+        global("mean", globals::avg),
+        global("median", globals::median),
+        global("mode", globals::mode),
+        global("variance", globals::variance),
+        global("varianceP", globals::variance_p),
+        global("stdev", globals::stdev),
+        global("stdevP", globals::stdev_p),
+        global("percentile", globals::percentile),
+        global("quantile", globals::quantile),
+        global("count", globals::count),
+        global("countIf", globals::count_if),
+        global("correl", globals::correl),
+        global("covariance", globals::covariance),
+
         constant("PI", core::f64::consts::PI),
         constant("π", core::f64::consts::PI),
         constant("e", core::f64::consts::E),
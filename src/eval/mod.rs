@@ -1,7 +1,12 @@
 mod test;
 pub mod context;
 pub mod operators;
+mod scope;
+mod pattern;
 mod globals;
+mod range;
+
+pub use range::Range;
 
 use alloc::{string::String, string::ToString, borrow::ToOwned, vec::Vec, boxed::Box, rc::Rc, format};
 use core::fmt::{Debug, Display, Formatter};
@@ -15,9 +20,10 @@ pub enum Object {
     Boolean(bool),
     Number(f64),
     String(String),
-    Function(Rc<dyn Fn(Vec<Object>) -> Result<Object>>),
+    Function(Rc<dyn Fn(Vec<Object>, HashMap<String, Object>) -> Result<Object>>),
     List(Vec<Object>),
     AssociativeArray(HashMap<String, Object>),
+    Range(Range),
 }
 
 impl Object {
@@ -25,10 +31,28 @@ impl Object {
         Self::String(str.as_ref().to_owned())
     }
 
+    /// A function taking positional arguments only. Passing it a named argument is an error.
     pub fn function(fun: impl Fn(Vec<Object>) -> Result<Object> + 'static) -> Self {
+        Self::function_with_named(move |args, named| match named.keys().next() {
+            Some(name) => Err(ManualError::UnexpectedArgument(name.clone()).into()),
+            None => fun(args),
+        })
+    }
+
+    /// A function which receives named arguments, such as the `precision` of `round(x, precision = 2)`, separately
+    /// from its positional ones.
+    pub fn function_with_named(fun: impl Fn(Vec<Object>, HashMap<String, Object>) -> Result<Object> + 'static) -> Self {
         Self::Function(Rc::new(fun))
     }
 
+    /// Calls a function, as [`Context::call_object`] does. Anything other than a function can't be called.
+    pub fn call(&self, arguments: Vec<Object>, named: HashMap<String, Object>) -> Result<Object> {
+        match self {
+            Object::Function(function) => function(arguments, named),
+            _ => Err(ManualError::CannotCallNonFunctionObject().into()),
+        }
+    }
+
     pub fn datatype(&self) -> &str {
         match self {
             Object::Nothing => "nothing",
@@ -38,6 +62,16 @@ impl Object {
             Object::List(_) => "list",
             Object::AssociativeArray(_) => "associative_array",
             Object::Function(_) => "function",
+            Object::Range(_) => "range",
+        }
+    }
+
+    /// The items of a list or range, producing the numbers of a range as they are needed.
+    pub fn items(&self) -> Option<Box<dyn Iterator<Item = Object> + '_>> {
+        match self {
+            Object::List(list) => Some(Box::new(list.iter().cloned())),
+            Object::Range(range) => Some(Box::new(range.iter().map(Object::Number))),
+            _ => None,
         }
     }
 }
@@ -52,6 +86,8 @@ impl Display for Object {
             Object::Function(_) => "fn()".to_owned(),
             Object::List(list) => format!("[{}]", list.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ")),
             Object::AssociativeArray(assoc) => format!("[{}]", assoc.iter().map(|(a, i)| format!("{}={}", a, i)).collect::<Vec<_>>().join(", ")),
+            Object::Range(range) if range.step.abs() == 1.0 => format!("{}..{}", range.start, range.end),
+            Object::Range(range) => format!("{}..{} step {}", range.start, range.end, range.step.abs()),
         })
     }
 }
@@ -77,6 +113,9 @@ impl Debug for Object {
             Object::AssociativeArray(arr) => f.debug_tuple("AssociativeArray")
                 .field(arr)
                 .finish(),
+            Object::Range(range) => f.debug_tuple("Range")
+                .field(range)
+                .finish(),
         }
     }
 }
@@ -121,6 +160,9 @@ impl PartialEq<Object> for Object {
             (Object::List(l), Object::List(r)) => l == r,
             (Object::AssociativeArray(l), Object::AssociativeArray(r)) => l == r,
 
+            (Object::Range(l), Object::Range(r)) => l == r,
+            (Object::Range(_), Object::List(_)) | (Object::List(_), Object::Range(_)) => self.items().unwrap().eq(other.items().unwrap()),
+
             _ => false
         }
     }
@@ -2,7 +2,10 @@ use crate::{
     error::*,
     eval::context::Operator,
     eval::context::OperatorBuilder,
+    eval::context::Thunk,
     eval::Object,
+    eval::Range,
+    parse::objects::Associativity,
     vec::Acc
 };
 use alloc::{
@@ -14,38 +17,48 @@ use alloc::{
 };
 use core::ops::Add;
 
+/// Equality binds tighter than `&&`, which binds tighter than `||`, so conditions such as `a == b && c || d` group
+/// as they do in most languages.
 pub(crate) fn get_standard_operators() -> Vec<Operator> {
     vec![
         OperatorBuilder::new()
             .symbol("==")
-            .precedence(1)
+            .precedence(4)
             .handler(compare)
             .build(),
 
         OperatorBuilder::new()
             .symbol("!=")
-            .precedence(1)
+            .precedence(4)
             .handler(inv_compare)
             .build(),
 
         OperatorBuilder::new()
             .symbol("&&")
             .precedence(3)
-            .handler(and)
+            .lazy_handler(lazy_and)
             .build(),
 
         OperatorBuilder::new()
             .symbol("||")
-            .precedence(3)
-            .handler(or)
+            .precedence(2)
+            .lazy_handler(lazy_or)
             .build(),
 
         OperatorBuilder::new()
             .symbol("!")
-            .precedence(3)
+            .operands(1)
+            .precedence(25)
             .handler(not)
             .build(),
 
+        OperatorBuilder::new()
+            .symbol("-")
+            .operands(1)
+            .precedence(25)
+            .handler(negate)
+            .build(),
+
         OperatorBuilder::new()
             .symbol(">")
             .precedence(5)
@@ -91,8 +104,59 @@ pub(crate) fn get_standard_operators() -> Vec<Operator> {
         OperatorBuilder::new()
             .symbol("^")
             .precedence(20)
+            .associativity(Associativity::Right)
             .handler(exponent)
             .build(),
+
+        OperatorBuilder::new()
+            .symbol("??")
+            .precedence(1)
+            .lazy_handler(coalesce)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("and")
+            .precedence(3)
+            .lazy_handler(lazy_and)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("or")
+            .precedence(2)
+            .lazy_handler(lazy_or)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("not")
+            .operands(1)
+            .precedence(25)
+            .handler(not)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("mod")
+            .precedence(15)
+            .handler(modulo)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("in")
+            .precedence(5)
+            .handler(contains)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("step")
+            .precedence(6)
+            .handler(step)
+            .build(),
+
+        OperatorBuilder::new()
+            .symbol("..")
+            .precedence(7)
+            .associativity(Associativity::None)
+            .handler(range)
+            .build(),
     ]
 }
 
@@ -126,37 +190,47 @@ pub fn inv_compare(args: &[Object]) -> Result<Object> {
     })
 }
 
-pub fn and(args: &[Object]) -> Result<Object> {
-    if let Some((first, remaining)) = args.split_first() {
-        let mut result = first.clone();
-
-        for arg in remaining {
-            result = match (result, arg) {
-                (Object::Boolean(l), Object::Boolean(r)) => Object::Boolean(l && *r),
-                _ => return Err(ManualError::OperationNotValidForType(format!("Attempt to and {} with {}", arg.datatype(), first.datatype())).into())
-            };
-        }
+/// Evaluates the right operand only when the left one is `true`.
+pub fn lazy_and(args: &[Thunk]) -> Result<Object> {
+    let [left, right] = args else {
+        return Err(ManualError::InsufficientOperands("And requires exactly two operands".to_owned()).into());
+    };
+
+    match left.evaluate()? {
+        Object::Boolean(false) => Ok(Object::Boolean(false)),
+        Object::Boolean(true) => match right.evaluate()? {
+            Object::Boolean(r) => Ok(Object::Boolean(r)),
+            r => Err(ManualError::OperationNotValidForType(format!("Attempt to and {} with boolean", r.datatype())).into())
+        },
+        l => Err(ManualError::OperationNotValidForType(format!("Attempt to and {}", l.datatype())).into())
+    }
+}
 
-        Ok(result)
-    } else {
-        Err(ManualError::InsufficientOperands("And requires at least two operands".to_owned()).into())
+/// Evaluates the right operand only when the left one is `false`.
+pub fn lazy_or(args: &[Thunk]) -> Result<Object> {
+    let [left, right] = args else {
+        return Err(ManualError::InsufficientOperands("Or requires exactly two operands".to_owned()).into());
+    };
+
+    match left.evaluate()? {
+        Object::Boolean(true) => Ok(Object::Boolean(true)),
+        Object::Boolean(false) => match right.evaluate()? {
+            Object::Boolean(r) => Ok(Object::Boolean(r)),
+            r => Err(ManualError::OperationNotValidForType(format!("Attempt to or {} with boolean", r.datatype())).into())
+        },
+        l => Err(ManualError::OperationNotValidForType(format!("Attempt to or {}", l.datatype())).into())
     }
 }
 
-pub fn or(args: &[Object]) -> Result<Object> {
-    if let Some((first, remaining)) = args.split_first() {
-        let mut result = first.clone();
+/// Evaluates the right operand only when the left one is `nothing`.
+pub fn coalesce(args: &[Thunk]) -> Result<Object> {
+    let [left, right] = args else {
+        return Err(ManualError::InsufficientOperands("Coalescing requires exactly two operands".to_owned()).into());
+    };
 
-        for arg in remaining {
-            result = match (result, arg) {
-                (Object::Boolean(l), Object::Boolean(r)) => Object::Boolean(l || *r),
-                _ => return Err(ManualError::OperationNotValidForType(format!("Attempt to or {} with {}", arg.datatype(), first.datatype())).into())
-            };
-        }
-
-        Ok(result)
-    } else {
-        Err(ManualError::InsufficientOperands("Or requires at least two operands".to_owned()).into())
+    match left.evaluate()? {
+        Object::Nothing => right.evaluate(),
+        value => Ok(value),
     }
 }
 
@@ -170,6 +244,14 @@ pub fn not(args: &[Object]) -> Result<Object> {
     Err(ManualError::InsufficientOperands("Not requires exactly one argument".to_owned()).into())
 }
 
+pub fn negate(args: &[Object]) -> Result<Object> {
+    match args {
+        [Object::Number(number)] => Ok(Object::Number(-number)),
+        [obj] => Err(ManualError::OperationNotValidForType(format!("Attempt to negate {}", obj.datatype())).into()),
+        _ => Err(ManualError::InsufficientOperands("Negation requires exactly one operand".to_owned()).into())
+    }
+}
+
 pub fn greater(args: &[Object]) -> Result<Object> {
     if let Some((first, remaining)) = args.split_first() {
         let mut result = first.clone();
@@ -215,6 +297,7 @@ pub fn add(args: &[Object]) -> Result<Object> {
                 (Object::Number(l), Object::Number(r)) => Object::Number(l + r),
                 (Object::String(l), Object::String(r)) => Object::String(String::new().add(&l).add(r)),
                 (Object::List(l), Object::List(r)) => Object::List(l.clone().into_iter().chain(r.clone().into_iter()).collect()),
+                (l @ (Object::List(_) | Object::Range(_)), r @ (Object::List(_) | Object::Range(_))) => Object::List(l.items().unwrap().chain(r.items().unwrap()).collect()),
                 (Object::List(l), r) => Object::List(l.clone().acc(r.clone())),
                 (Object::AssociativeArray(l), Object::AssociativeArray(r)) => Object::AssociativeArray(l.clone().into_iter().chain(r.clone().into_iter()).collect()),
                 _ => return Err(ManualError::OperationNotValidForType(format!("Attempt to add {} to {}", arg.datatype(), first.datatype())).into())
@@ -310,4 +393,32 @@ pub fn exponent(args: &[Object]) -> Result<Object> {
     } else {
         Err(ManualError::InsufficientOperands("Exponentiation requires at least two operands".to_owned()).into())
     }
-}
\ No newline at end of file
+}
+
+pub fn contains(args: &[Object]) -> Result<Object> {
+    match args {
+        [item, Object::List(list)] => Ok(Object::Boolean(list.contains(item))),
+        [Object::Number(number), Object::Range(range)] => Ok(Object::Boolean(range.contains(*number))),
+        [_, Object::Range(_)] => Ok(Object::Boolean(false)),
+        [Object::String(key), Object::AssociativeArray(arr)] => Ok(Object::Boolean(arr.contains_key(key))),
+        [Object::String(needle), Object::String(haystack)] => Ok(Object::Boolean(haystack.contains(needle.as_str()))),
+        [item, container] => Err(ManualError::OperationNotValidForType(format!("Attempt to find {} in {}", item.datatype(), container.datatype())).into()),
+        _ => Err(ManualError::InsufficientOperands("Membership requires exactly two operands".to_owned()).into())
+    }
+}
+
+pub fn range(args: &[Object]) -> Result<Object> {
+    match args {
+        [Object::Number(start), Object::Number(end)] => Ok(Object::Range(Range::new(*start, *end))),
+        [start, end] => Err(ManualError::OperationNotValidForType(format!("Attempt to create a range from {} to {}", start.datatype(), end.datatype())).into()),
+        _ => Err(ManualError::InsufficientOperands("Ranges require exactly two operands".to_owned()).into())
+    }
+}
+
+pub fn step(args: &[Object]) -> Result<Object> {
+    match args {
+        [Object::Range(range), Object::Number(step)] => Ok(Object::Range(range.step(*step)?)),
+        [range, step] => Err(ManualError::OperationNotValidForType(format!("Attempt to step {} by {}", range.datatype(), step.datatype())).into()),
+        _ => Err(ManualError::InsufficientOperands("Stepping requires exactly two operands".to_owned()).into())
+    }
+}
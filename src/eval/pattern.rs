@@ -0,0 +1,114 @@
+use crate::error::*;
+use crate::eval::scope::Scope;
+use crate::eval::Object;
+use crate::parse::objects::*;
+use crate::{Context, DataSource};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use nom::lib::std::collections::HashMap;
+
+impl<Provider: DataSource> Context<Provider> {
+    /// Binds the parts of `object` named by `pattern`, evaluating defaults in `scope`. An object without the shape the
+    /// pattern describes is a [`ManualError::NoMatchingPattern`] explaining the difference, in which case `bindings`
+    /// may be incomplete.
+    pub(crate) fn destructure(&self, pattern: &Pattern, object: &Object, scope: &Scope, bindings: &mut HashMap<String, Object>) -> Result<()> {
+        match pattern {
+            Pattern::Wildcard => Ok(()),
+            Pattern::Name(name) => {
+                bindings.insert(name.clone(), object.clone());
+                Ok(())
+            },
+            Pattern::Literal(literal) => {
+                let expected = match literal {
+                    Literal::Nothing => Object::Nothing,
+                    Literal::Bool(bool) => Object::Boolean(*bool),
+                    Literal::Number(number) => Object::Number(*number),
+                    Literal::String(string) => Object::String(string.clone()),
+                    literal => return mismatch(format!("{:?} cannot be matched against", literal)),
+                };
+
+                match object == &expected {
+                    true => Ok(()),
+                    false => mismatch(format!("Expected {}, found {}", expected, object)),
+                }
+            },
+            Pattern::Type { datatype, name } => {
+                if object.datatype() != datatype {
+                    return mismatch(format!("Expected {}, found {}", datatype, object.datatype()));
+                }
+
+                if let Some(name) = name {
+                    bindings.insert(name.clone(), object.clone());
+                }
+
+                Ok(())
+            },
+            Pattern::List { items: patterns, rest } => {
+                let items = match (object, object.items()) {
+                    (_, Some(items)) => items.collect::<Vec<_>>(),
+                    // `[]` is an empty associative array as well as an empty list
+                    (Object::AssociativeArray(array), None) if array.is_empty() => Vec::new(),
+                    (_, None) => return mismatch(format!("Expected a list, found {}", object.datatype())),
+                };
+                let required = patterns.iter().filter(|pattern| !matches!(pattern, Pattern::Default { .. })).count();
+
+                if items.len() < required || (rest.is_none() && items.len() > patterns.len()) {
+                    return mismatch(match rest {
+                        None if required == patterns.len() => format!("Expected a list of {} items, found {}", patterns.len(), items.len()),
+                        None => format!("Expected a list of {} to {} items, found {}", required, patterns.len(), items.len()),
+                        Some(_) => format!("Expected a list of at least {} items, found {}", required, items.len()),
+                    });
+                }
+
+                for (index, pattern) in patterns.iter().enumerate() {
+                    self.destructure_element(pattern, items.get(index), scope, bindings)?;
+                }
+
+                match rest {
+                    Some(rest) => self.destructure(rest, &Object::List(items.into_iter().skip(patterns.len()).collect()), scope, bindings),
+                    None => Ok(()),
+                }
+            },
+            Pattern::AssociativeArray { entries, rest } => {
+                let Object::AssociativeArray(array) = object else {
+                    return mismatch(format!("Expected an associative array, found {}", object.datatype()));
+                };
+
+                for (Key::Name(key) | Key::String(key), pattern) in entries {
+                    match (array.get(key), pattern) {
+                        (None, Pattern::Default { .. }) | (Some(_), _) => self.destructure_element(pattern, array.get(key), scope, bindings)?,
+                        (None, _) => return mismatch(format!("Expected an associative array with the key `{}`", key)),
+                    }
+                }
+
+                match rest {
+                    Some(rest) => self.destructure(rest, &Object::AssociativeArray(array.iter()
+                        .filter(|(key, _)| !entries.iter().any(|(Key::Name(name) | Key::String(name), _)| name == *key))
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect()), scope, bindings),
+                    None => Ok(()),
+                }
+            },
+            Pattern::Default { .. } => self.destructure_element(pattern, Some(object), scope, bindings),
+        }
+    }
+
+    /// Binds an item or argument which may be missing. Defaults are used in place of missing items or `nothing`, and
+    /// may refer to the names bound before them.
+    pub(crate) fn destructure_element(&self, pattern: &Pattern, object: Option<&Object>, scope: &Scope, bindings: &mut HashMap<String, Object>) -> Result<()> {
+        match (pattern, object) {
+            (Pattern::Default { pattern, default }, None | Some(Object::Nothing)) => {
+                let default = self.evaluate_value(default, &scope.with(bindings.clone()))?;
+                self.destructure(pattern, &default, scope, bindings)
+            },
+            (Pattern::Default { pattern, .. }, Some(object)) => self.destructure(pattern, object, scope, bindings),
+            (pattern, Some(object)) => self.destructure(pattern, object, scope, bindings),
+            (_, None) => mismatch("Missing a value without a default".into()),
+        }
+    }
+}
+
+fn mismatch(reason: String) -> Result<()> {
+    Err(ManualError::NoMatchingPattern(reason).into())
+}
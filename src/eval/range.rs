@@ -0,0 +1,74 @@
+use crate::error::*;
+use crate::ManualError;
+use alloc::borrow::ToOwned;
+
+/// How far from a whole number of steps a number may be while still counting as one.
+const TOLERANCE: f64 = 1e-9;
+
+/// An inclusive sequence of numbers such as `1..10` or `0..100 step 5`. Its items are only produced as they are
+/// needed, so indexing or testing membership of a large range costs nothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: f64,
+    pub end: f64,
+    /// Negative when the range counts down.
+    pub step: f64,
+}
+
+impl Range {
+    /// Counts from `start` towards `end` in steps of one.
+    pub fn new(start: f64, end: f64) -> Self {
+        Self {
+            start,
+            end,
+            step: if end < start { -1.0 } else { 1.0 },
+        }
+    }
+
+    /// Takes steps of `step` in the direction the range already counts.
+    pub fn step(self, step: f64) -> Result<Self> {
+        if !(step > 0.0 && step.is_finite()) {
+            return Err(ManualError::OperationNotValidForType("The step of a range must be a positive number".to_owned()).into());
+        }
+
+        Ok(Self {
+            step: step.copysign(self.step),
+            ..self
+        })
+    }
+
+    /// The number of items, which is `usize::MAX` for ranges too large to count.
+    pub fn len(&self) -> usize {
+        // Allow for rounding error so `0..0.3 step 0.1` still ends on 0.3
+        match self.steps() + TOLERANCE {
+            steps if steps >= 0.0 && steps.is_finite() => (steps as usize).saturating_add(1),
+            _ => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<f64> {
+        (index < self.len()).then_some(self.start + self.step * index as f64)
+    }
+
+    /// Whether `number` is one of the items, allowing for the same rounding error as [`Range::len`].
+    pub fn contains(&self, number: f64) -> bool {
+        let index = (number - self.start) / self.step;
+        let nearest = index.round();
+
+        !self.is_empty() && (index - nearest).abs() < TOLERANCE && nearest >= 0.0 && nearest <= self.steps() + TOLERANCE
+    }
+
+    /// The number of steps from the start to the end, which is fractional when the end isn't an item.
+    fn steps(&self) -> f64 {
+        (self.end - self.start) / self.step
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = f64> {
+        let range = *self;
+        (0..self.len()).map(move |index| range.start + range.step * index as f64)
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use nom::lib::std::collections::HashMap;
+use crate::eval::Object;
+
+/// The chain of local bindings visible at some point of an expression, innermost first. Locals shadow globals.
+///
+/// Scopes are never modified once created, which lets any number of closures share the scope they were created in.
+#[derive(Clone, Default)]
+pub(crate) struct Scope(Option<Rc<Frame>>);
+
+struct Frame {
+    bindings: HashMap<String, Object>,
+    parent: Scope,
+}
+
+impl Scope {
+    /// A new scope nested inside this one.
+    pub(crate) fn with(&self, bindings: HashMap<String, Object>) -> Self {
+        Self(Some(Rc::new(Frame {
+            bindings,
+            parent: self.clone(),
+        })))
+    }
+
+    /// Looks up the innermost binding of `name`.
+    pub(crate) fn get(&self, name: &str) -> Option<&Object> {
+        let frame = self.0.as_ref()?;
+
+        frame.bindings.get(name)
+            .or_else(|| frame.parent.get(name))
+    }
+}
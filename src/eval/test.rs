@@ -6,9 +6,12 @@ mod tests {
         eval::context::Context,
         error::*,
         eval::*,
+        eval::context::OperatorBuilder,
+        parse::expression::Fixity,
         DataSource,
     };
 
+    #[derive(Clone)]
     pub struct ManualProvider {
         values: Vec<String>,
     }
@@ -22,6 +25,20 @@ mod tests {
         }
     }
 
+    /// The reason given by the error `expression` fails with, which must be one explaining what was wrong.
+    #[track_caller]
+    fn error_reason(cx: &Context<impl DataSource>, expression: &str) -> String {
+        match cx.evaluate(expression).map_err(Error::into_inner) {
+            Err(global::Inner::ManualError(
+                ManualError::ExpectedType(reason)
+                | ManualError::InsufficientOperands(reason)
+                | ManualError::OperationNotValidForType(reason)
+                | ManualError::NoMatchingPattern(reason)
+            )) => reason,
+            result => panic!("Expected an error, found {:?}", result),
+        }
+    }
+
     #[test]
     fn test_eval() -> Result<()> {
         let cx = Context::new(ManualProvider {
@@ -136,4 +153,725 @@ mod tests {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_multiline_formula() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"
+            // Scale the values before summing them
+            ([
+                x = 2 * 3,   /* six */
+                y = 4
+            ]).x + 1
+        "#)?, 7.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_operators() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("x", Object::Number(4.0));
+
+        assert_eq!(cx.evaluate(r#"!true"#)?, Object::Boolean(false));
+        assert_eq!(cx.evaluate(r#"-x"#)?, -4.0);
+        assert_eq!(cx.evaluate(r#"-x * 2"#)?, -8.0);
+        assert_eq!(cx.evaluate(r#"10 - -x"#)?, 14.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_postfix_operators() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_operator(OperatorBuilder::new()
+            .symbol("!")
+            .fixity(Fixity::Postfix)
+            .precedence(30)
+            .handler(|args| match args {
+                [Object::Number(n)] => Ok(Object::Number((1..=*n as u64).product::<u64>() as f64)),
+                _ => Ok(Object::Nothing),
+            })
+            .build());
+
+        assert_eq!(cx.evaluate(r#"5!"#)?, 120.0);
+        assert_eq!(cx.evaluate(r#"1 + 3!"#)?, 7.0);
+        assert_eq!(cx.evaluate(r#"!false"#)?, Object::Boolean(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_associativity() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"10-2-3"#)?, 5.0);
+        assert_eq!(cx.evaluate(r#"8/4/2"#)?, 1.0);
+        assert_eq!(cx.evaluate(r#"17 % 10 % 4"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"2^3^2"#)?, 512.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlapping_operators() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_operator(OperatorBuilder::new()
+            .symbol("<<")
+            .precedence(12)
+            .handler(|args| match args {
+                [Object::Number(base), Object::Number(shift)] => Ok(Object::Number(((*base as i64) << (*shift as i64)) as f64)),
+                _ => Ok(Object::Nothing),
+            })
+            .build());
+
+        assert_eq!(cx.evaluate(r#"1 << 4"#)?, 16.0);
+        assert_eq!(cx.evaluate(r#"1 < 4"#)?, Object::Boolean(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_operators() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"7 mod 4"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"true and not false"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#"false or 1 > 2"#)?, Object::Boolean(false));
+        assert_eq!(cx.evaluate(r#"2 in [1, 2, 3]"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#""ell" in "Hello""#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#""z" in [a = 1]"#)?, Object::Boolean(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_circuit() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("x", Object::Nothing)
+            .with_global("y", Object::AssociativeArray(vec![("a".to_owned(), Object::Number(2.0))].into_iter().collect()));
+
+        assert_eq!(cx.evaluate(r#"x != nothing && x.a > 1"#)?, Object::Boolean(false));
+        assert_eq!(cx.evaluate(r#"y != nothing && y.a > 1"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#"x == nothing || x.a > 1"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#"false and undefined_value"#)?, Object::Boolean(false));
+        assert_matches!(cx.evaluate(r#"true && undefined_value"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_operator() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_operator(OperatorBuilder::new()
+            .symbol("otherwise")
+            .precedence(1)
+            .lazy_handler(|args| match args[0].evaluate() {
+                Ok(value) => Ok(value),
+                Err(_) => args[1].evaluate(),
+            })
+            .build());
+
+        assert_eq!(cx.evaluate(r#"undefined_value otherwise 3"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"1 + 1 otherwise undefined_value"#)?, 2.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_conditional() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("x", Object::Number(4.0));
+
+        assert_eq!(cx.evaluate(r#"if x > 3 then "big" else "small""#)?, Object::string("big"));
+        assert_eq!(cx.evaluate(r#"x > 5 ? "big" : "small""#)?, Object::string("small"));
+        assert_eq!(cx.evaluate(r#"x < 0 ? -1 : x == 0 ? 0 : 1"#)?, 1.0);
+
+        // Only the chosen branch is evaluated
+        assert_eq!(cx.evaluate(r#"if x == 4 then 1 else undefined_value"#)?, 1.0);
+        assert_eq!(cx.evaluate(r#"x != 4 ? undefined_value : 2"#)?, 2.0);
+
+        assert_matches!(cx.evaluate(r#"if x then 1 else 2"#), Err(_));
+
+        Ok(())
+    }
+
+    fn apply(args: Vec<Object>) -> Result<Object> {
+        match args.split_first() {
+            Some((Object::Function(f), args)) => f(args.to_vec(), Default::default()),
+            _ => Err(ManualError::CannotCallNonFunctionObject().into()),
+        }
+    }
+
+    #[test]
+    fn test_lambda() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("apply", Object::function(apply))
+            .with_global("x", Object::Number(100.0));
+
+        assert_eq!(cx.evaluate(r#"((x, y) => x + y)(1, 2)"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"apply(x => x * 2, 4)"#)?, 8.0);
+        assert_eq!(cx.evaluate(r#"apply(() => x)"#)?, 100.0);
+
+        // Surplus arguments are ignored, missing ones are an error
+        assert_eq!(cx.evaluate(r#"apply(a => a, 1, 2)"#)?, 1.0);
+        assert_matches!(cx.evaluate(r#"apply((a, b) => a, 1)"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_closure() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("apply", Object::function(apply));
+
+        assert_eq!(cx.evaluate(r#"apply(apply(x => y => x - y, 10), 3)"#)?, 7.0);
+
+        // Parameters shadow globals only within the lambda
+        assert_eq!(cx.evaluate(r#"apply(apply => apply + 1, 1)"#)?, 2.0);
+        assert_matches!(cx.evaluate(r#"apply"#), Ok(Object::Function(_)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_closure_data_source() -> Result<()> {
+        // Neither `Clone` nor `'static`, so lambdas can't hold on to the provider itself
+        struct Cells(Vec<f64>);
+
+        impl DataSource for Cells {
+            fn query(&self, query: impl AsRef<str>) -> Option<Object> {
+                self.0.get(query.as_ref().parse::<usize>().ok()?).copied().map(Object::Number)
+            }
+        }
+
+        let cells = Cells(vec![10.0, 20.0]);
+        let cx = Context::new(&cells);
+
+        assert_eq!(cx.evaluate(r#"map([1, 2], x => x * {0})"#)?, Object::List(vec![Object::Number(10.0), Object::Number(20.0)]));
+        assert_eq!(cx.evaluate(r#"((x ?? {1}) => x + 1)()"#)?, 21.0);
+        assert_eq!(cx.evaluate(r#"(x => y => match y { 0 => {0}, _ => {1} + x })(1)(2)"#)?, 21.0);
+        assert_eq!(cx.evaluate_program("let sum(n) = n == 0 ? {9} ?? 0 : {0} + sum(n - 1)\nsum(3)")?, 30.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_let() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("x", Object::Number(100.0))
+            .with_global("apply", Object::function(apply));
+
+        assert_eq!(cx.evaluate(r#"let x = 2 + 3 in x * x"#)?, 25.0);
+        assert_eq!(cx.evaluate(r#"let a = 2; b = a * 3; a + b"#)?, 8.0);
+        assert_eq!(cx.evaluate(r#"let x = 1 in let x = x + 1 in x"#)?, 2.0);
+
+        // Bindings don't leak into globals
+        assert_eq!(cx.evaluate(r#"(let x = 1 in x) + x"#)?, 101.0);
+        assert_matches!(cx.evaluate(r#"(let y = 1 in y) + y"#), Err(_));
+
+        // Closures capture the bindings visible where they are written
+        assert_eq!(cx.evaluate(r#"let n = 10; add = x => x + n; apply(add, 5)"#)?, 15.0);
+        assert_eq!(cx.evaluate(r#"let two = 2 in 2 in [1, two]"#)?, Object::Boolean(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chained_access() -> Result<()> {
+        let row = Object::AssociativeArray(vec![
+            ("name".to_owned(), Object::string("Widget")),
+            ("values".to_owned(), Object::List(vec![Object::Number(3.0), Object::Number(4.0)])),
+            ("price".to_owned(), Object::AssociativeArray(vec![("net".to_owned(), Object::Number(9.5))].into_iter().collect())),
+        ].into_iter().collect());
+
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("row", row.clone())
+            .with_global("identity", Object::function(|args| Ok(args[0].clone())));
+
+        assert_eq!(cx.evaluate(r#"row.price.net"#)?, 9.5);
+        assert_eq!(cx.evaluate(r#"row.values.0"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"row["values"][1 + 0]"#)?, 4.0);
+        assert_eq!(cx.evaluate(r#"identity(row).name"#)?, Object::string("Widget"));
+        assert_eq!(cx.evaluate(r#"(x => y => x * y)(3)(4)"#)?, 12.0);
+        assert_eq!(cx.evaluate(r#"[f = x => x + 1].f(1)"#)?, 2.0);
+        assert_eq!(cx.evaluate(r#""héllo"[1]"#)?, Object::string("é"));
+        assert_eq!(cx.evaluate(r#"1.5 + 1"#)?, 2.5);
+
+        assert_matches!(cx.evaluate(r#"row.values[2]"#), Err(_));
+        assert_matches!(cx.evaluate(r#"row.values[0.5]"#), Err(_));
+        assert_matches!(cx.evaluate(r#"row.name.first"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional_chaining() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("row", Object::AssociativeArray(vec![
+            ("values".to_owned(), Object::List(vec![Object::Number(3.0)])),
+        ].into_iter().collect()));
+
+        // Missing cells are `nothing`
+        assert_eq!(cx.evaluate(r#"{5}?.total"#)?, Object::Nothing);
+        assert_eq!(cx.evaluate(r#"{5}?.total.net"#)?, Object::Nothing);
+        assert_eq!(cx.evaluate(r#"{5}?.[0]"#)?, Object::Nothing);
+        assert_eq!(cx.evaluate(r#"{5}?.(1, 2)"#)?, Object::Nothing);
+        assert_eq!(cx.evaluate(r#"row?.values?.[0]"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"true ?.5 : 1"#)?, 0.5);
+
+        assert_matches!(cx.evaluate(r#"{5}.total"#), Err(_));
+        assert_matches!(cx.evaluate(r#"row?.missing"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coalesce() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"{5} ?? 0"#)?, 0.0);
+        assert_eq!(cx.evaluate(r#"{0} ?? undefined_value"#)?, Object::string("Hello"));
+        assert_eq!(cx.evaluate(r#"{5}?.total ?? {6} ?? 1 + 1"#)?, 2.0);
+        assert_eq!(cx.evaluate(r#"false ?? true"#)?, Object::Boolean(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pipeline() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("sum", Object::function(|args| operators::add(&args)))
+            .with_global("double", Object::function(|args| operators::multiply(&[args[0].clone(), Object::Number(2.0)])))
+            .with_global("subtract", Object::function(|args| operators::subtract(&args)));
+
+        assert_eq!(cx.evaluate(r#"1 |> sum(2, 3)"#)?, 6.0);
+        assert_eq!(cx.evaluate(r#"10 |> subtract(3) |> double"#)?, 14.0);
+        assert_eq!(cx.evaluate(r#"1 + 2 |> double() |> (x => x + 1)"#)?, 7.0);
+        assert_eq!(cx.evaluate(r#"let f = x => x |> double in 4 |> f"#)?, 8.0);
+
+        assert_matches!(cx.evaluate(r#"1 |> 2"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("items", Object::List(vec![Object::Number(1.0), Object::Number(2.0)]));
+
+        assert_eq!(cx.evaluate(r#"`Total: ${items[0] + items[1]} items`"#)?, "Total: 3 items");
+        assert_eq!(cx.evaluate(r#"`${items} and ${nothing} or ${true}`"#)?, "1, 2 and nothing or true");
+        assert_eq!(cx.evaluate(r#"let name = 'World' in `Hello, ${name}!`"#)?, "Hello, World!");
+
+        assert_eq!(cx.evaluate(r#"`${1234567.891:,.2}`"#)?, "1,234,567.89");
+        assert_eq!(cx.evaluate(r#"`${-1234:,}`"#)?, "-1,234");
+        assert_eq!(cx.evaluate(r#"`${0.5:.100}`"#)?, format!("0.5{}", "0".repeat(99)).as_str());
+        assert_eq!(cx.evaluate(r#"`${0.256:.1%}`"#)?, "25.6%");
+        assert_eq!(cx.evaluate(r#"`${2/3:.3}`"#)?, "0.667");
+
+        assert_matches!(cx.evaluate(r#"`${'text':.2}`"#), Err(_));
+
+        assert_eq!(cx.evaluate(r#"toString(1.5)"#)?, "1.5");
+        assert_matches!(cx.evaluate(r#"toString(1, 2)"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"1..4"#)?, Object::List(vec![Object::Number(1.0), Object::Number(2.0), Object::Number(3.0), Object::Number(4.0)]));
+        assert_eq!(cx.evaluate(r#"0..10 step 5"#)?, Object::List(vec![Object::Number(0.0), Object::Number(5.0), Object::Number(10.0)]));
+        assert_eq!(cx.evaluate(r#"3..1"#)?, Object::List(vec![Object::Number(3.0), Object::Number(2.0), Object::Number(1.0)]));
+        assert_eq!(cx.evaluate(r#"`${0..1 step 0.25}`"#)?, "0, 0.25, 0.5, 0.75, 1");
+
+        // Large ranges are never materialised to be indexed or searched
+        assert_eq!(cx.evaluate(r#"(0..1e15 step 2)[1000]"#)?, 2000.0);
+        assert_eq!(cx.evaluate(r#"999998 in 0..1e15 step 2"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#"999999 in 0..1e15 step 2"#)?, Object::Boolean(false));
+
+        assert_eq!(cx.evaluate(r#"(1..2) + [5] + (8..9)"#)?, Object::List(vec![Object::Number(1.0), Object::Number(2.0), Object::Number(5.0), Object::Number(8.0), Object::Number(9.0)]));
+
+        // Bounds may be computed, so ranges are parenthesised to be concatenated
+        assert_eq!(cx.evaluate(r#"1..1 + 1"#)?, Object::List(vec![Object::Number(1.0), Object::Number(2.0)]));
+        assert_eq!(cx.evaluate(r#"(1..3) + [4]"#)?, Object::List(vec![Object::Number(1.0), Object::Number(2.0), Object::Number(3.0), Object::Number(4.0)]));
+        assert_matches!(cx.evaluate(r#"1..3 + [4]"#), Err(_));
+
+        assert_matches!(cx.evaluate(r#"1..5 step 0"#), Err(_));
+        assert_matches!(cx.evaluate(r#"(1..5)[5]"#), Err(_));
+        assert_matches!(cx.evaluate(r#"'a'..'z'"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_bounds() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        // Ranges too long to count have the largest possible length rather than overflowing
+        assert_eq!(cx.evaluate(r#"len(0..1e30)"#)?, usize::MAX as f64);
+        assert_eq!(cx.evaluate(r#"(0..1e30)[3]"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"take(1..1e30, 3)"#)?, Object::List(vec![Object::Number(1.0), Object::Number(2.0), Object::Number(3.0)]));
+        assert_eq!(cx.evaluate(r#"[5 in 0..1e300, 1e300 in 0..1e300, -1 in 0..1e300]"#)?, Object::List(vec![Object::Boolean(true), Object::Boolean(true), Object::Boolean(false)]));
+
+        // Membership allows for the same rounding error as the length does
+        assert_eq!(cx.evaluate(r#"[0.3 in 0..1 step 0.1, 0.7 in 0..1 step 0.1, 0.35 in 0..1 step 0.1, 1.1 in 0..1 step 0.1]"#)?,
+            Object::List(vec![Object::Boolean(true), Object::Boolean(true), Object::Boolean(false), Object::Boolean(false)]));
+        assert_eq!(cx.evaluate(r#"[len(0..0.3 step 0.1), (0..0.3 step 0.1)[3] == 0.1 * 3]"#)?, Object::List(vec![Object::Number(4.0), Object::Boolean(true)]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spread() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("apply", Object::function(apply))
+            .with_global("list", Object::List(vec![Object::Number(1.0), Object::Number(2.0)]));
+
+        assert_eq!(cx.evaluate(r#"[...list, 3] == [1, 2, 3]"#)?, Object::Boolean(true));
+        assert_eq!(cx.evaluate(r#"[0, ...1..3, ...[]]"#)?, Object::List(vec![Object::Number(0.0), Object::Number(1.0), Object::Number(2.0), Object::Number(3.0)]));
+        assert_eq!(cx.evaluate(r#"apply((a, b, c) => a + b * c, ...list, 10)"#)?, 21.0);
+
+        // Later entries replace earlier ones
+        assert_eq!(cx.evaluate(r#"let base = [x = 1, y = 2] in [...base, x = 5].x + [x = 5, ...base].x"#)?, 6.0);
+
+        assert_matches!(cx.evaluate(r#"[...1]"#), Err(_));
+        assert_matches!(cx.evaluate(r#"[...list, x = 1]"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_named_arguments() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("round", Object::function_with_named(|args, named| match (args.as_slice(), named.get("precision")) {
+            ([Object::Number(x)], None) => Ok(Object::Number(x.round())),
+            ([Object::Number(x)], Some(Object::Number(precision))) => Ok(Object::Number((x * 10f64.powf(*precision)).round() / 10f64.powf(*precision))),
+            _ => Err(ManualError::ExpectedType("Number".to_owned()).into()),
+        })).with_global("positional", Object::function(|args| Ok(Object::Number(args.len() as f64))));
+
+        assert_eq!(cx.evaluate(r#"round(1.23456)"#)?, 1.0);
+        assert_eq!(cx.evaluate(r#"round(1.23456, precision = 2)"#)?, 1.23);
+        assert_eq!(cx.evaluate(r#"1.23456 |> round(precision = 3)"#)?, 1.235);
+        assert_eq!(cx.evaluate(r#"let options = [precision = 1] in round(1.23456, ...options)"#)?, 1.2);
+
+        // Lambdas accept their parameters by name
+        assert_eq!(cx.evaluate(r#"((x, y) => x - y)(y = 1, x = 3)"#)?, 2.0);
+        assert_eq!(cx.evaluate(r#"((x, y) => x - y)(3, y = 1)"#)?, 2.0);
+        assert_matches!(cx.evaluate(r#"((x, y) => x - y)(3, z = 1)"#), Err(_));
+
+        // Functions which don't take named arguments reject them
+        assert_eq!(cx.evaluate(r#"positional(1, 2)"#)?, 2.0);
+        assert_matches!(cx.evaluate(r#"positional(1, precision = 2)"#).map_err(Error::into_inner), Err(global::Inner::ManualError(ManualError::UnexpectedArgument(name))) if name == "precision");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        let describe = r#"x => match x {
+            nothing => 'nothing',
+            0 => 'zero',
+            number n => `number ${n}`,
+            string s => s,
+            [] => 'empty',
+            [a, b] => `pair ${a + b}`,
+            [name = string n, age = a] => `${n} is ${a}`,
+            list _ => 'list',
+            _ => 'other',
+        }"#;
+
+        let cases = [
+            ("nothing", "nothing"),
+            ("0", "zero"),
+            ("5", "number 5"),
+            ("'text'", "text"),
+            ("[]", "empty"),
+            ("[1, 2]", "pair 3"),
+            ("1..2", "pair 3"),
+            ("[name = 'Ann', age = 30, extra = true]", "Ann is 30"),
+            ("[name = 1, age = 30]", "other"),
+            ("[1, 2, 3]", "list"),
+            ("true", "other"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(cx.evaluate(format!("({})({})", describe, value))?, expected);
+        }
+
+        // Bindings are local to their arm
+        assert_matches!(cx.evaluate(r#"match 1 { n => n } + n"#), Err(_));
+        assert_matches!(cx.evaluate(r#"match 1 { string s => s }"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_destructuring() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("apply", Object::function(apply));
+
+        assert_eq!(cx.evaluate(r#"let [first, second] = 1..2 in first - second"#)?, -1.0);
+        assert_eq!(cx.evaluate(r#"let [first, ...rest] = [1, 2, 3] in rest"#)?, Object::List(vec![Object::Number(2.0), Object::Number(3.0)]));
+        assert_eq!(cx.evaluate(r#"let [a, b ?? a * 10, c ?? b] = [1] in [a, b, c]"#)?, Object::List(vec![Object::Number(1.0), Object::Number(10.0), Object::Number(10.0)]));
+        assert_eq!(cx.evaluate(r#"let [x = a, y = [b, _], z = c ?? 3, ...others] = [x = 1, y = [2, 0], w = 4] in a + b + c + others.w"#)?, 10.0);
+
+        assert_eq!(cx.evaluate(r#"(([x = a, y = b]) => a + b)([x = 1, y = 2])"#)?, 3.0);
+        assert_eq!(cx.evaluate(r#"apply(([a, b], c ?? 5) => a * b + c, [2, 3])"#)?, 11.0);
+        assert_eq!(cx.evaluate(r#"((x, y ?? 1) => x - y)(y = nothing, x = 3)"#)?, 2.0);
+
+        assert_eq!(error_reason(&cx, r#"let [a, b] = [1, 2, 3] in a"#), "Expected a list of 2 items, found 3");
+        assert_eq!(error_reason(&cx, r#"let [a, b ?? 1, ...c] = [] in a"#), "Expected a list of at least 1 items, found 0");
+        assert_eq!(error_reason(&cx, r#"let [x = a] = [y = 1] in a"#), "Expected an associative array with the key `x`");
+        assert_eq!(error_reason(&cx, r#"(([a]) => a)(5)"#), "Expected a list, found number");
+        assert_eq!(error_reason(&cx, r#"let string s = 5 in s"#), "Expected string, found number");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        let program = r#"
+            // Helpers come first, then the result
+            let rate = 0.2
+            let tax(price) = price * rate
+            let [net, gross] = [100, 100 + tax(100)]; gross -
+                net
+        "#;
+
+        assert_eq!(cx.evaluate_program(program)?, 20.0);
+        assert_eq!(cx.evaluate_program("let fact(n) = n < 2 ? 1 : n * fact(n - 1)\nfact(5)")?, 120.0);
+        assert_eq!(cx.evaluate_program("let x = 1; let x = x + 1; x")?, 2.0);
+        assert_eq!(cx.evaluate_program("let x = 1; y = 2; x + y")?, 3.0);
+        assert_eq!(cx.evaluate_program("let x = 1; y = 2 in x + y")?, 3.0);
+        assert_eq!(cx.evaluate_program("let x = 1; [x, 2, [a = 3].a, ...[4]]")?, Object::List(vec![Object::Number(1.0), Object::Number(2.0), Object::Number(3.0), Object::Number(4.0)]));
+
+        // A line starting with an operator which may be a prefix is a statement of its own
+        assert_eq!(cx.evaluate_program("let a = 5\n-a")?, -5.0);
+        assert_eq!(cx.evaluate_program("let a = 5\n* 2\na")?, 10.0);
+        assert_eq!(cx.evaluate_program("max(1,\n-2)")?, 1.0);
+        assert_eq!(cx.evaluate_program("let x = 1")?, Object::Nothing);
+
+        // Definitions last only as long as the program
+        assert_matches!(cx.evaluate("rate"), Err(_));
+        assert_matches!(cx.evaluate_program("tax(1)"), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_math() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        assert_eq!(cx.evaluate(r#"abs(-2) + sqrt(9) + floor(1.7) + ceil(1.2) + trunc(-1.7)"#)?, 7.0);
+        assert_eq!(cx.evaluate(r#"[sign(-3), sign(0), sign(2)]"#)?, Object::List(vec![Object::Number(-1.0), Object::Number(0.0), Object::Number(1.0)]));
+        assert_eq!(cx.evaluate(r#"[log(1000), log(8, 2), ln(exp(2))]"#)?, Object::List(vec![Object::Number(3.0), Object::Number(3.0), Object::Number(2.0)]));
+        assert_eq!(cx.evaluate(r#"[round(2.5), round(-2.5), round(1.23456, 2), round(1250, -2)]"#)?, Object::List(vec![Object::Number(3.0), Object::Number(-3.0), Object::Number(1.23), Object::Number(1300.0)]));
+        assert_eq!(cx.evaluate(r#"[min(3, 1, 2), max(3, 1, 2), clamp(12, 0, 10), clamp(-1, 0, 10)]"#)?, Object::List(vec![Object::Number(1.0), Object::Number(3.0), Object::Number(10.0), Object::Number(0.0)]));
+        assert_eq!(cx.evaluate(r#"[hypot(3, 4), gcd(12, -18), lcm(4, 6), factorial(5), factorial(0)]"#)?, Object::List(vec![Object::Number(5.0), Object::Number(6.0), Object::Number(12.0), Object::Number(120.0), Object::Number(1.0)]));
+        assert_eq!(cx.evaluate(r#"factorial(200)"#)?, f64::INFINITY);
+
+        assert_eq!(error_reason(&cx, r#"sqrt('4')"#), "sqrt expects a number as argument 1, found string");
+        assert_eq!(error_reason(&cx, r#"clamp(1, 0, [])"#), "clamp expects a number as argument 3, found associative_array");
+        assert_eq!(error_reason(&cx, r#"round(1, 0.5)"#), "round expects an integer as argument 2, found 0.5");
+        assert_eq!(error_reason(&cx, r#"factorial(-1)"#), "factorial expects a non-negative integer as argument 1, found -1");
+        assert_eq!(error_reason(&cx, r#"atan2(1)"#), "atan2 expects a number as argument 2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strings() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        let string = |expression: &str| cx.evaluate(expression).map(|obj| match obj {
+            Object::String(string) => string,
+            obj => panic!("Expected a string, found {:?}", obj),
+        });
+
+        assert_eq!(cx.evaluate(r#"[len('héllo'), len([1, 2]), len(1..10)]"#)?, Object::List(vec![Object::Number(5.0), Object::Number(2.0), Object::Number(10.0)]));
+        assert_eq!(string(r#"upper(' ab ') + lower('CD') + trim('  e  ')"#)?, " AB cde");
+        assert_eq!(string(r#"join(split('a,b,,c', ','), '|') + join(split('añb'), '-') + join(1..3)"#)?, "a|b||ca-ñ-b123");
+        assert_eq!(string(r#"replace('a-b-c', '-', ' + ')"#)?, "a + b + c");
+        assert_eq!(cx.evaluate(r#"[contains('label', 'be'), startsWith('label', 'la'), endsWith('label', 'la'), indexOf('über', 'er'), indexOf('a', 'b')]"#)?,
+            Object::List(vec![Object::Boolean(true), Object::Boolean(true), Object::Boolean(false), Object::Number(2.0), Object::Number(-1.0)]));
+
+        // Indices count characters rather than bytes
+        assert_eq!(string(r#"substring('naïve', 1, 3) + substring('naïve', 3, 1) + substring('naïve', 3)"#)?, "aïaïve");
+        assert_eq!(string(r#"slice('naïve', -3) + slice('naïve', 0, -3) + slice('naïve', 4, 2)"#)?, "ïvena");
+
+        assert_eq!(string(r#"padStart('7', 3, '0') + padEnd('ab', 5, '.-') + padStart('long', 2) + padEnd('x', 2)"#)?, "007ab.-.longx ");
+        assert_eq!(string(r#"repeat('ab', 3) + repeat('ab', 0)"#)?, "ababab");
+
+        assert_matches!(cx.evaluate(r#"upper(5)"#).map_err(Error::into_inner), Err(global::Inner::ManualError(ManualError::ExpectedType(reason)))
+            if reason == "upper expects a string as argument 1, found 5");
+        assert_matches!(cx.evaluate(r#"repeat('a', -1)"#), Err(_));
+
+        // Lengths which would exhaust memory are refused rather than attempted
+        assert_eq!(error_reason(&cx, r#"repeat('ab', 1e19)"#), "repeat expects a count of at most 8388608 as argument 2, found 10000000000000000000");
+        assert_eq!(error_reason(&cx, r#"padStart('a', 1e19)"#), "padStart expects a length of at most 16777216 as argument 2, found 10000000000000000000");
+        assert_eq!(error_reason(&cx, r#"padEnd('a', 1e19, '-')"#), "padEnd expects a length of at most 16777216 as argument 2, found 10000000000000000000");
+        assert_eq!(string(r#"repeat('', 1e19)"#)?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_functions() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        let numbers = |expression: &str| cx.evaluate(expression).map(|obj| match obj {
+            Object::List(list) => list.into_iter().map(|item| match item {
+                Object::Number(number) => number,
+                item => panic!("Expected a number, found {:?}", item),
+            }).collect::<Vec<_>>(),
+            obj => panic!("Expected a list, found {:?}", obj),
+        });
+
+        assert_eq!(numbers(r#"map([1, 2, 3], x => x * 2)"#)?, [2.0, 4.0, 6.0]);
+        assert_eq!(numbers(r#"map(1..3, (x, i) => x * i)"#)?, [0.0, 2.0, 6.0]);
+        assert_eq!(numbers(r#"map([4, 9], sqrt)"#)?, [2.0, 3.0]);
+        assert_eq!(numbers(r#"1..10 |> filter(x => x % 3 == 0)"#)?, [3.0, 6.0, 9.0]);
+        assert_eq!(cx.evaluate(r#"[reduce(1..4, (a, b) => a * b), fold([], (a, b) => a + b, 0), reduce(['a', 'b'], (a, b) => a + b, '>')]"#)?,
+            Object::List(vec![Object::Number(24.0), Object::Number(0.0), Object::string(">ab")]));
+        assert_eq!(cx.evaluate(r#"[any([1, 2], x => x > 1), all([1, 2], x => x > 1), find(1..100, x => x * x > 50), find([], x => true)]"#)?,
+            Object::List(vec![Object::Boolean(true), Object::Boolean(false), Object::Number(8.0), Object::Nothing]));
+
+        // Sorting is stable, so equal keys stay in their original order
+        assert_eq!(cx.evaluate(r#"map(sortBy([[k = 2, v = 'a'], [k = 1, v = 'b'], [k = 2, v = 'c']], (a, b) => a.k - b.k), x => x.v)"#)?,
+            Object::List(vec![Object::string("b"), Object::string("a"), Object::string("c")]));
+        assert_eq!(numbers(r#"reverse(unique([1, 2, 1, 3, 2]))"#)?, [3.0, 2.0, 1.0]);
+        assert_eq!(numbers(r#"flatten([1, [2, [3]], 4..5], 2)"#)?, [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(numbers(r#"map(zip([1, 2, 3], 10..11), ([a, b]) => a + b)"#)?, [11.0, 13.0]);
+        assert_eq!(numbers(r#"map(enumerate([5, 6]), ([i, x]) => i * x)"#)?, [0.0, 6.0]);
+        assert_eq!(numbers(r#"take(1..1000000000, 3) + skip(1..5, 3)"#)?, [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(numbers(r#"map(chunk(1..5, 2), len)"#)?, [2.0, 2.0, 1.0]);
+        assert_eq!(cx.evaluate(r#"let groups = groupBy(1..5, x => x % 2 == 0 ? 'even' : 'odd') in [len(groups.odd), len(groups.even)]"#)?,
+            Object::List(vec![Object::Number(3.0), Object::Number(2.0)]));
+
+        assert_matches!(cx.evaluate(r#"filter([1], x => x)"#).map_err(Error::into_inner), Err(global::Inner::ManualError(ManualError::ExpectedType(reason)))
+            if reason == "filter expects its function to return a boolean, found number");
+        assert_matches!(cx.evaluate(r#"sortBy([2, 1], (a, b) => a.missing)"#), Err(_));
+        assert_matches!(cx.evaluate(r#"map(1, x => x)"#), Err(_));
+        assert_matches!(cx.evaluate(r#"reduce([], (a, b) => a)"#), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_associative_array_functions() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        });
+
+        let record = r#"[name = 'Ada', age = 36, address = [city = 'London', street = 'St James']]"#;
+        let evaluate = |expression: &str| cx.evaluate(format!("let record = {} in {}", record, expression));
+
+        assert_eq!(evaluate("keys(record)")?, Object::List(vec![Object::string("address"), Object::string("age"), Object::string("name")]));
+        assert_eq!(evaluate("values([b = 2, a = 1])")?, Object::List(vec![Object::Number(1.0), Object::Number(2.0)]));
+        assert_eq!(evaluate("entries([a = 1])")?, Object::List(vec![Object::List(vec![Object::string("a"), Object::Number(1.0)])]));
+        assert_eq!(evaluate("fromEntries(map(entries(record), ([k, v]) => [upper(k), v])).NAME")?, Object::string("Ada"));
+        assert_eq!(evaluate("fromEntries([])")?, Object::AssociativeArray(Default::default()));
+
+        assert_eq!(evaluate("[has(record, 'age'), has(record, 'email'), get(record, 'email', 'none'), get(record, 'email'), get(record, 'age', 0)]")?,
+            Object::List(vec![Object::Boolean(true), Object::Boolean(false), Object::string("none"), Object::Nothing, Object::Number(36.0)]));
+
+        // `set` and `without` leave the original untouched
+        assert_eq!(evaluate("let older = set(record, 'age', 37) in [older.age, record.age]")?, Object::List(vec![Object::Number(37.0), Object::Number(36.0)]));
+        assert_eq!(evaluate("keys(without(record, 'age', 'address', 'missing'))")?, Object::List(vec![Object::string("name")]));
+
+        assert_eq!(evaluate("mapValues([a = 1, b = 2], (v, k) => k + toString(v * 10)).b")?, Object::string("b20"));
+        assert_eq!(evaluate("keys(filterEntries(record, (v, k) => k != 'age' && v != 36))")?, Object::List(vec![Object::string("address"), Object::string("name")]));
+
+        // Nested arrays are merged rather than replaced
+        assert_eq!(evaluate("let moved = merge(record, [address = [city = 'Paris']], [age = 37]) in [moved.address.city, moved.address.street, moved.age]")?,
+            Object::List(vec![Object::string("Paris"), Object::string("St James"), Object::Number(37.0)]));
+
+        assert_matches!(evaluate("fromEntries([['a', 1], [2, 'b']])").map_err(Error::into_inner), Err(global::Inner::ManualError(ManualError::ExpectedType(reason)))
+            if reason == "fromEntries expects a list of [key, value] pairs as argument 1, found list");
+        assert_matches!(evaluate("keys([1, 2])"), Err(_));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics() -> Result<()> {
+        let cx = Context::new(ManualProvider {
+            values: vec!["Hello".to_owned()]
+        }).with_global("column", Object::List(vec![Object::Number(2.0), Object::Nothing, Object::Number(4.0), Object::Number(4.0), Object::Number(5.0)]));
+
+        // Numbers may be given directly or in lists and ranges, which are flattened, skipping `nothing`
+        assert_eq!(cx.evaluate(r#"sum(1..100)"#)?, 5050.0);
+        assert_eq!(cx.evaluate(r#"sum(1, [2, [3, nothing]], 4..5) + sum()"#)?, 15.0);
+        assert_eq!(cx.evaluate(r#"[avg(column), mean(1, 2), median(column), median(3, 1, 2), mode(column), mode(1, 2)]"#)?,
+            Object::List(vec![Object::Number(3.75), Object::Number(1.5), Object::Number(4.0), Object::Number(2.0), Object::Number(4.0), Object::Number(1.0)]));
+
+        assert_eq!(cx.evaluate(r#"[variance(2, 4, 4, 4, 5, 5, 7, 9), varianceP(2, 4, 4, 4, 5, 5, 7, 9), stdevP([2, 4, 4, 4, 5, 5, 7, 9])]"#)?,
+            Object::List(vec![Object::Number(32.0 / 7.0), Object::Number(4.0), Object::Number(2.0)]));
+        assert_eq!(cx.evaluate(r#"stdev(1, 3)"#)?, core::f64::consts::SQRT_2);
+
+        assert_eq!(cx.evaluate(r#"[percentile(1..5, 50), percentile([10, 20], 25), quantile(column, 0), quantile(column, 1)]"#)?,
+            Object::List(vec![Object::Number(3.0), Object::Number(12.5), Object::Number(2.0), Object::Number(5.0)]));
+
+        assert_eq!(cx.evaluate(r#"[count(column), count(['a', nothing, 1..3]), countIf(column, x => x > 3)]"#)?,
+            Object::List(vec![Object::Number(4.0), Object::Number(4.0), Object::Number(3.0)]));
+        assert_eq!(cx.evaluate(r#"count(0..1e30)"#)?, usize::MAX as f64);
+
+        // Pairs where either side is missing are left out
+        assert_eq!(cx.evaluate(r#"[covariance([1, 2, 3, nothing], [2, 4, 6, 100]), correl([1, 2, 3], [3, 2, 1]), correl(1..4, [1, nothing, 3, 4])]"#)?,
+            Object::List(vec![Object::Number(2.0), Object::Number(-1.0), Object::Number(1.0)]));
+
+        assert_eq!(error_reason(&cx, r#"sum(1, ['a'])"#), "sum expects numbers as argument 2, found string");
+        assert_eq!(error_reason(&cx, r#"avg([])"#), "avg expects at least 1 number, found 0");
+        assert_eq!(error_reason(&cx, r#"stdev(1)"#), "stdev expects at least 2 numbers, found 1");
+        assert_eq!(error_reason(&cx, r#"percentile(1..5, 150)"#), "percentile expects a number between 0 and 100 as argument 2, found 150");
+        assert_eq!(error_reason(&cx, r#"correl([1, 2], [1, 2, 3])"#), "correl expects lists of the same length, found 2 and 3");
+
+        Ok(())
+    }
+}
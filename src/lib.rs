@@ -17,6 +17,8 @@ pub use crate::eval::context::*;
 pub use crate::parse::literal::Address;
 pub use crate::parse::literal::Column;
 pub use crate::parse::value::Value;
+pub use crate::parse::span::{Location, Span};
+pub use crate::parse::expression::{Associativity, Fixity};
 
 /// # Data Source
 /// A datasource which responds to queries.
@@ -36,6 +38,7 @@ impl<T: DataSource> DataSource for &T {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct EmptyProvider;
 
 impl EmptyProvider {
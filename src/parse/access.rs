@@ -1,32 +1,52 @@
 use alloc::boxed::Box;
 use nom::IResult;
+use crate::parse::error::closing;
+use crate::parse::key::Key;
 use crate::parse::literal::Literal;
 use crate::parse::ParseContext;
 use crate::parse::value::{value_parser, Value};
 use crate::parse::parser;
+use crate::parse::whitespace::token;
+use crate::parse::span::{Span, Spanned};
 
+/// A member of a value named in the source, such as `row.total`, `row."Unit Price"` or `list.0`.
 #[derive(Debug, PartialEq)]
 pub struct Access {
     pub(crate) left: Box<Value>,
-    pub(crate) member: Literal
+    pub(crate) member: Spanned<Literal>,
+    /// Whether this is written `?.`, which evaluates to `nothing` when `left` is `nothing`.
+    pub optional: bool,
+    pub span: Span,
 }
 
 impl Access {
-    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
-        parser::map(parser::tuple((
-            parser::alt((
-                parser::map(Literal::parse, Value::Literal),
-                parser::delimited(
-                    parser::char('('),
-                    value_parser(cx.clone()),
-                    parser::char(')'),
-                )
-            )),
-            parser::char('.'),
-            Literal::parse
-        )), |(obj, _, name)| Self {
-            left: Box::new(obj),
-            member: name
-        })(input)
+    /// . ( name | string | integer )
+    pub(super) fn member<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Spanned<Literal>> {
+        parser::preceded(token(parser::char('.')), |input| Spanned::parse(input, cx, Access::name))(input)
     }
-}
\ No newline at end of file
+
+    /// name | string | integer
+    pub(super) fn name(input: &str) -> IResult<&str, Literal> {
+        parser::alt((
+            // Only whole numbers, so `list.0.1` reads as two accesses rather than the number `0.1`
+            parser::map(parser::map_res(parser::digit1, str::parse::<u32>), |index| Literal::Number(index as f64)),
+            parser::map(Key::parse, Literal::from),
+        ))(input)
+    }
+}
+
+/// A member of a value computed at runtime, such as `list[i + 1]`.
+#[derive(Debug, PartialEq)]
+pub struct Index {
+    pub left: Box<Value>,
+    pub index: Box<Value>,
+    pub optional: bool,
+    pub span: Span,
+}
+
+impl Index {
+    /// [ value ]
+    pub(super) fn index<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+        parser::preceded(parser::char('['), parser::terminated(value_parser(cx.clone()), closing(cx, ']', "index", input)))(input)
+    }
+}
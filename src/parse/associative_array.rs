@@ -2,22 +2,60 @@ use alloc::vec::Vec;
 use nom::IResult;
 use crate::{
     parse::key::*,
+    parse::span::Span,
+    parse::spread::Spread,
     parse::value::Value
 };
 use crate::parse::{parser, ParseContext};
+use crate::parse::error::{closing, expect, Expected};
+use crate::parse::whitespace::token;
 use crate::parse::value::value_parser;
 
 #[derive(Debug, PartialEq)]
 pub struct AssociativeArray {
-    pub items: Vec<(Key, Value)>
+    pub items: Vec<Entry>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Entry {
+    Pair(Key, Value),
+    /// Copies the entries of another associative array. Later entries replace earlier ones with the same key.
+    Spread(Spread),
+}
+
+impl Entry {
+    pub fn value(&self) -> &Value {
+        match self {
+            Entry::Pair(_, value) => value,
+            Entry::Spread(spread) => &spread.value,
+        }
+    }
 }
 
 impl AssociativeArray {
+    /// Spreads alone don't make an associative array, so `[...a]` is a list.
     pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
-        parser::map(parser::delimited(parser::char('['), parser::separated_list0(parser::char(','), parser::tuple((Key::parse, parser::char('='), value_parser(cx)))), parser::char(']')), |items| AssociativeArray {
-            items: items.into_iter()
-                .map(|(key, _, value)| (key, value))
-                .collect()
-        })(input)
+        let entry = parser::alt((
+            parser::map(|input| Spread::parse(input, cx.clone()), Entry::Spread),
+            parser::map(
+                parser::tuple((token(Key::parse), expect(&cx, Expected::Token("="), token(parser::char('='))), value_parser(cx.clone()))),
+                |(key, _, value)| Entry::Pair(key, value),
+            ),
+        ));
+
+        let (remaining, items) = parser::delimited(
+            parser::char('['),
+            parser::verify(
+                parser::separated_list0(token(parser::char(',')), entry),
+                |items: &Vec<Entry>| items.is_empty() || items.iter().any(|entry| matches!(entry, Entry::Pair(..))),
+            ),
+            closing(&cx, ']', "associative array", input),
+        )(input)?;
+
+        Ok((remaining, AssociativeArray {
+            items,
+            span: cx.span(input, remaining),
+        }))
     }
-}
\ No newline at end of file
+}
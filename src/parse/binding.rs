@@ -0,0 +1,54 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use nom::IResult;
+use crate::{
+    parse::error::{expect, Expected},
+    parse::key::keyword,
+    parse::parser,
+    parse::pattern::Pattern,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::open_value_parser,
+    parse::value::Value,
+    parse::whitespace::token,
+};
+
+/// Names the results of expressions for use within `body`. Each binding can refer to those before it, and may
+/// destructure its value, as in `let [first, ...rest] = list`.
+#[derive(Debug, PartialEq)]
+pub struct Let {
+    pub bindings: Vec<(Pattern, Value)>,
+    pub body: Box<Value>,
+    pub span: Span,
+}
+
+impl Let {
+    /// let pattern = value (; pattern = value)* (in | ;) value
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        // `==` and `=>` continue an expression, so a body such as `x == 1` isn't mistaken for another binding
+        let binding = |input| parser::separated_pair(
+            |input| Pattern::parse(input, &cx),
+            expect(&cx, Expected::Token("="), token(|input| parser::terminated(parser::char('='), parser::not(parser::one_of("=>")))(input))),
+            cx.terminated_by("in", open_value_parser(cx.clone())),
+        )(input);
+
+        let (remaining, (_, first, mut bindings, _, body)) = parser::tuple((
+            keyword("let"),
+            &binding,
+            parser::many0(parser::preceded(token(parser::char(';')), &binding)),
+            parser::alt((
+                expect(&cx, Expected::Token("in"), token(keyword("in"))),
+                expect(&cx, Expected::Token(";"), token(parser::tag(";"))),
+            )),
+            open_value_parser(cx.clone()),
+        ))(input)?;
+
+        bindings.insert(0, first);
+
+        Ok((remaining, Let {
+            bindings,
+            body: Box::new(body),
+            span: cx.span(input, remaining),
+        }))
+    }
+}
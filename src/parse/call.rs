@@ -1,31 +1,69 @@
 use alloc::boxed::Box;
+use alloc::string::String;
 use alloc::vec::Vec;
 use nom::IResult;
 use crate::{
+    parse::key::parse_name,
     parse::parser,
     parse::ParseContext,
+    parse::spread::Spread,
     parse::value::value_parser,
     parse::value::Value
 };
-use crate::parse::literal::Literal;
+use crate::parse::error::closing;
+use crate::parse::whitespace::token;
+use crate::parse::span::Span;
 
 #[derive(Debug, PartialEq)]
 pub struct Call {
     pub name: Box<Value>,
-    pub arguments: Vec<Value>
+    /// Positional arguments, which may be spreads of lists.
+    pub arguments: Vec<Value>,
+    /// Arguments given by name, such as `precision = 2`, in the order they were written.
+    pub named: Vec<(String, Value)>,
+    /// Whether this is written `?.()`, which evaluates to `nothing` when `name` is `nothing`.
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// The positional and named arguments of a call, in the order they were written.
+pub(super) type Arguments = (Vec<Value>, Vec<(String, Value)>);
+
+/// A single argument of a call.
+enum Argument {
+    Positional(Value),
+    Named(String, Value),
 }
 
 impl Call {
-    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
-        parser::map(parser::tuple((
-            parser::alt((
-                parser::delimited(parser::char('('), value_parser(cx.clone()), parser::char(')')),
-                parser::map(Literal::parse, Value::Literal),
-            )),
-            // value_parser(cx.clone()),
-            parser::delimited(parser::char('('), parser::separated_list0(parser::char(','), value_parser(cx.clone())), parser::char(')')),
-        )), |(name, arguments)| Call {
-            name: Box::new(name), arguments,
-        })(input)
+    /// ( value | ...value | name = value, * )
+    pub(super) fn arguments<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Arguments> {
+        let named = parser::map(
+            parser::pair(token(parameter), value_parser(cx.clone())),
+            |(name, value)| Argument::Named(name, value),
+        );
+
+        let (remaining, arguments) = parser::delimited(
+            parser::char('('),
+            parser::separated_list0(token(parser::char(',')), parser::alt((
+                named,
+                parser::map(Spread::or_value(cx.clone()), Argument::Positional),
+            ))),
+            closing(cx, ')', "call", input),
+        )(input)?;
+
+        Ok((remaining, arguments.into_iter().fold((Vec::new(), Vec::new()), |(mut positional, mut named), argument| {
+            match argument {
+                Argument::Positional(value) => positional.push(value),
+                Argument::Named(name, value) => named.push((name, value)),
+            }
+
+            (positional, named)
+        })))
     }
-}
\ No newline at end of file
+}
+
+/// The `name =` of a named argument, which mustn't be confused with `==` or a lambda.
+fn parameter(input: &str) -> IResult<&str, String> {
+    parser::terminated(parse_name, parser::pair(token(parser::char('=')), parser::not(parser::one_of("=>"))))(input)
+}
@@ -0,0 +1,42 @@
+use alloc::boxed::Box;
+use nom::IResult;
+use crate::{
+    parse::error::{expect, Expected},
+    parse::key::keyword,
+    parse::parser,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::{open_value_parser, value_parser},
+    parse::value::Value,
+    parse::whitespace::token,
+};
+
+/// Chooses between two values. Only the chosen branch is evaluated.
+#[derive(Debug, PartialEq)]
+pub struct Conditional {
+    pub condition: Box<Value>,
+    pub then: Box<Value>,
+    pub otherwise: Box<Value>,
+    pub span: Span,
+}
+
+impl Conditional {
+    /// if condition then value else value
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let (remaining, (_, condition, _, then, _, otherwise)) = parser::tuple((
+            keyword("if"),
+            value_parser(cx.clone()),
+            expect(&cx, Expected::Token("then"), token(keyword("then"))),
+            value_parser(cx.clone()),
+            expect(&cx, Expected::Token("else"), token(keyword("else"))),
+            open_value_parser(cx.clone()),
+        ))(input)?;
+
+        Ok((remaining, Conditional {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+            span: cx.span(input, remaining),
+        }))
+    }
+}
@@ -0,0 +1,220 @@
+use crate::parse::span::{Location, Source};
+use crate::parse::template::FormatSpec;
+use crate::parse::whitespace::{token, whitespace};
+use crate::parse::ParseContext;
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use nom::IResult;
+
+/// # Parse Error
+/// Describes why an expression could not be parsed, in terms a spreadsheet author can act on.
+///
+/// ```rust
+/// use expression::Context;
+/// use expression::EmptyProvider;
+///
+/// use expression::error::global::Inner;
+///
+/// let cx = Context::new(EmptyProvider::new());
+/// let Inner::ParserError(err) = cx.parse("[1,2").unwrap_err().into_inner() else {
+///     panic!("Expected a parse error");
+/// };
+///
+/// assert_eq!((err.location.line, err.location.column), (1, 5));
+/// assert_eq!(err.message(), "expected closing `]` for list opened at 1:1, found end of input");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Where parsing stopped making progress.
+    pub location: Location,
+    /// Human-readable descriptions of what would have allowed parsing to continue.
+    pub expected: Vec<String>,
+    /// The character found at `location`, or `None` at the end of the input.
+    pub found: Option<char>,
+    /// A suggestion on how to fix the expression, if one is known.
+    pub hint: Option<String>,
+    line: String,
+}
+
+impl ParseError {
+    pub fn message(&self) -> String {
+        let expected = match self.expected.as_slice() {
+            [] => "an expression".to_owned(),
+            [only] => only.clone(),
+            [init @ .., last] => format!("{} or {}", init.join(", "), last),
+        };
+
+        match self.found {
+            Some(found) => format!("expected {}, found `{}`", expected, found),
+            None => format!("expected {}, found end of input", expected),
+        }
+    }
+
+    /// Renders the offending line with a caret pointing at the error location.
+    pub fn snippet(&self) -> String {
+        let gutter = format!("{}", self.location.line);
+        let padding = " ".repeat(gutter.len());
+
+        format!("{padding} |\n{gutter} | {line}\n{padding} | {caret}^",
+            line = self.line,
+            caret = " ".repeat(self.location.column - 1))
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at {}:{}\n{}", self.message(), self.location.line, self.location.column, self.snippet())?;
+
+        if let Some(hint) = &self.hint {
+            write!(f, "\nhint: {}", hint)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Something the parser was looking for when it failed.
+#[derive(Clone, Copy)]
+pub(crate) enum Expected<'a> {
+    Expression,
+    Pattern,
+    Number,
+    Format,
+    End,
+    Separator,
+    Operand(&'a str),
+    Token(&'static str),
+    Grouping(&'a str),
+    Closing {
+        delimiter: char,
+        construct: &'static str,
+        opened: &'a str,
+    },
+}
+
+impl Expected<'_> {
+    fn describe(&self, source: &Source) -> String {
+        match self {
+            Expected::Expression => "an expression".to_owned(),
+            Expected::Pattern => "a pattern".to_owned(),
+            Expected::Number => "a number which fits in 64 bits".to_owned(),
+            Expected::Format => format!("a format such as `,.2%` with at most {} decimal places", FormatSpec::MAX_PRECISION),
+            Expected::End => "end of input".to_owned(),
+            Expected::Separator => "`;` or a new line".to_owned(),
+            Expected::Operand(operator) => format!("operand after `{}`", operator),
+            Expected::Token(token) => format!("`{}`", token),
+            Expected::Grouping(operator) => format!("parentheses around the first `{}`, as it cannot be chained", operator),
+            Expected::Closing { delimiter, construct, opened } => {
+                let opened = source.locate_remaining(opened);
+                format!("closing `{}` for {} opened at {}:{}", delimiter, construct, opened.line, opened.column)
+            }
+        }
+    }
+
+    fn hint(&self) -> Option<String> {
+        match self {
+            Expected::End => Some("remove the trailing input, or combine it with the expression using an operator".to_owned()),
+            Expected::Number => Some("write numbers this large in decimal or scientific notation, such as `1e30`".to_owned()),
+            Expected::Format => Some("use `,` to separate thousands, `.` and a number of decimal places, and `%` for percentages".to_owned()),
+            Expected::Separator => Some("start each statement on a new line or end it with a `;`".to_owned()),
+            Expected::Operand(operator) => Some(format!("remove the trailing `{}` or add a value after it", operator)),
+            Expected::Grouping(operator) => Some(format!("`{}` is non-associative, so write `(a {0} b) {0} c` or `a {0} (b {0} c)`", operator)),
+            Expected::Closing { delimiter, construct, .. } => Some(format!("add a `{}` to close the {}", delimiter, construct)),
+            _ => None,
+        }
+    }
+}
+
+/// The furthest point any parser reached before failing. Since the grammar backtracks freely, the failure furthest
+/// into the input is almost always the one closest to what the author meant to write.
+#[derive(Default)]
+pub(crate) struct Furthest {
+    offset: usize,
+    expected: Vec<String>,
+    hint: Option<String>,
+    generic: bool,
+    /// Whether the input at this point is wrong regardless of what else might have been expected, as with a number
+    /// which is too large.
+    invalid: bool,
+}
+
+impl ParseContext {
+    /// Records that `expected` would have allowed parsing to continue at `input`.
+    pub(crate) fn record(&self, input: &str, expected: Expected) {
+        let Some(source) = self.source.as_ref() else {
+            return;
+        };
+
+        let offset = source.text().len() - input.len();
+        let mut furthest = self.furthest.borrow_mut();
+        let fallback = matches!(expected, Expected::Expression);
+        let invalid = matches!(expected, Expected::Number);
+
+        // Nothing else could have been written instead of invalid input, so it is reported alone.
+        if offset == furthest.offset && furthest.invalid {
+            return;
+        }
+
+        // "an expression" is only reported when nothing more specific was expected at the same point.
+        if offset == furthest.offset && !furthest.generic && fallback && !furthest.expected.is_empty() {
+            return;
+        }
+
+        if offset > furthest.offset || furthest.expected.is_empty() || (furthest.generic && !fallback) || (invalid && offset == furthest.offset) {
+            *furthest = Furthest {
+                offset,
+                expected: Vec::new(),
+                hint: expected.hint(),
+                generic: fallback,
+                invalid,
+            };
+        } else if offset < furthest.offset {
+            return;
+        }
+
+        let description = expected.describe(source);
+        if !furthest.expected.contains(&description) {
+            furthest.expected.push(description);
+        }
+    }
+
+    /// Builds an error describing the furthest failure, or a failure at `input` if nothing further was recorded.
+    pub(crate) fn error(&self, input: &str) -> ParseError {
+        let source = self.source.as_ref().expect("Errors can only be reported while parsing a source");
+        let furthest = self.furthest.borrow();
+
+        let offset = source.text().len() - input.len();
+        let (offset, expected, hint) = if furthest.offset >= offset && !furthest.expected.is_empty() {
+            (furthest.offset, furthest.expected.clone(), furthest.hint.clone())
+        } else {
+            (offset, Vec::new(), None)
+        };
+
+        let text = source.text();
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+
+        ParseError {
+            location: source.locate(offset),
+            expected,
+            found: text[offset..].chars().next(),
+            hint,
+            line: text[line_start..line_end].to_owned(),
+        }
+    }
+}
+
+/// Records `expected` at the current position, past any whitespace, if `parser` fails.
+pub(crate) fn expect<'c, 'a: 'c, O>(cx: &'c ParseContext, expected: Expected<'a>, parser: impl Fn(&'a str) -> IResult<&'a str, O> + 'c) -> impl Fn(&'a str) -> IResult<&'a str, O> + 'c {
+    move |input| parser(input).inspect_err(|_| cx.record(whitespace(input).map_or(input, |(input, _)| input), expected))
+}
+
+/// Parses the closing `delimiter` of a `construct` which began at `opened`.
+pub(crate) fn closing<'c, 'a: 'c>(cx: &'c ParseContext, delimiter: char, construct: &'static str, opened: &'a str) -> impl Fn(&'a str) -> IResult<&'a str, char> + 'c {
+    expect(cx, Expected::Closing { delimiter, construct, opened }, token(nom::character::complete::char(delimiter)))
+}
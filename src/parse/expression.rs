@@ -1,3 +1,5 @@
+use crate::parse::key::is_name_char;
+use crate::parse::span::Span;
 use crate::parse::value::Value;
 use alloc::{
     borrow::ToOwned,
@@ -6,17 +8,70 @@ use alloc::{
     vec::Vec,
 };
 
+/// Where an operator is written relative to its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Fixity {
+    /// Written before its only operand, such as `!x`.
+    Prefix,
+    /// Written between its two operands, such as `x + y`.
+    Infix,
+    /// Written after its only operand, such as `5!`.
+    Postfix,
+}
+
+/// How a chain of infix operators with equal precedence is grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Associativity {
+    /// `a - b - c` is read as `(a - b) - c`.
+    #[default]
+    Left,
+    /// `a ^ b ^ c` is read as `a ^ (b ^ c)`.
+    Right,
+    /// `a..b..c` is rejected, the grouping must be made explicit with parentheses.
+    None,
+}
+
+/// How the parser recognises an operator.
+#[derive(Debug, Clone)]
+pub struct OperatorSyntax {
+    pub symbol: String,
+    pub precedence: i64,
+    pub fixity: Fixity,
+    pub associativity: Associativity,
+}
+
+impl OperatorSyntax {
+    /// Word operators such as `and` end in a character which may continue a name, so they only match when not followed
+    /// by another one. Otherwise `android` would be read as `and roid`.
+    pub(crate) fn is_word(&self) -> bool {
+        self.symbol.chars().last().is_some_and(is_name_char)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Expression {
     pub operands: Vec<Value>,
     pub operator: String,
+    pub fixity: Fixity,
+    pub span: Span,
 }
 
 impl Expression {
-    pub(super) fn build_value(expr: (Value, &str, Value)) -> Value {
+    pub(super) fn infix(left: Value, operator: &str, right: Value, span: Span) -> Value {
         Value::Expression(Self {
-            operands: vec![expr.0, expr.2],
-            operator: expr.1.to_owned(),
+            operands: vec![left, right],
+            operator: operator.to_owned(),
+            fixity: Fixity::Infix,
+            span,
         })
     }
-}
\ No newline at end of file
+
+    pub(super) fn unary(operator: &str, fixity: Fixity, operand: Value, span: Span) -> Value {
+        Value::Expression(Self {
+            operands: vec![operand],
+            operator: operator.to_owned(),
+            fixity,
+            span,
+        })
+    }
+}
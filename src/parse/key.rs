@@ -23,24 +23,21 @@ impl Key {
     }
 }
 
-fn parse_name(input: &str) -> IResult<&str, String> {
+pub(crate) fn parse_name(input: &str) -> IResult<&str, String> {
     let mut name = String::with_capacity(input.len());
 
     let mut iter = input.chars();
 
-    if let Some(c) = iter.next() {
-        if nom_unicode::is_alphabetic(c) || c == '_' || c == '$' {
-            name.push(c);
-        } else {
-            return Err(nom::Err::Error(nom::error::Error {
-                input,
-                code: nom::error::ErrorKind::NonEmpty,
-            }));
-        }
+    match iter.next() {
+        Some(c) if nom_unicode::is_alphabetic(c) || c == '_' || c == '$' => name.push(c),
+        _ => return Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::NonEmpty,
+        })),
     }
 
     while let Some(c) = iter.next() {
-        if nom_unicode::is_alphanumeric(c) || c == '_' || c == '$' {
+        if is_name_char(c) {
             name.push(c);
         } else {
             break;
@@ -50,6 +47,16 @@ fn parse_name(input: &str) -> IResult<&str, String> {
     Ok((&input[name.len()..], name))
 }
 
+/// Whether `c` may appear within a name after its first character.
+pub(crate) fn is_name_char(c: char) -> bool {
+    nom_unicode::is_alphanumeric(c) || c == '_' || c == '$'
+}
+
+/// Matches `word`, provided it isn't the start of a longer name.
+pub(crate) fn keyword<'a>(word: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    move |input| parser::terminated(parser::tag(word), parser::not(parser::satisfy(is_name_char)))(input)
+}
+
 fn parse_unicode<'a, E>(input: &'a str) -> IResult<&'a str, char, E>
 where
     E: ParseError<&'a str> + FromExternalError<&'a str, ParseIntError>,
@@ -82,6 +89,8 @@ where
             parser::value('/', parser::char('/')),
             parser::value('"', parser::char('"')),
             parser::value('\'', parser::char('\'')),
+            parser::value('`', parser::char('`')),
+            parser::value('$', parser::char('$')),
         )),
     )(input)
 }
@@ -113,28 +122,42 @@ pub(crate) fn parse_string<'a>(start: char, end: char) -> impl Fn(&'a str) -> IR
 
         let parse_literal = parser::verify(parser::is_not(terminator), |s: &str| !s.is_empty());
 
-        let fragment = parser::alt((
-            parser::map(parse_literal, StringFragment::Literal),
-            parser::map(parse_escaped_char, StringFragment::EscapedChar),
-            parser::value(StringFragment::EscapedWS, parse_escaped_whitespace),
-        ));
-
-        let parse_raw_string = nom::multi::fold_many0(
-            fragment,
-            String::new,
-            |mut string, fragment| match fragment {
-                StringFragment::Literal(lit) => {
-                    string.push_str(lit);
-                    string
-                }
-                StringFragment::EscapedChar(c) => {
-                    string.push(c);
-                    string
-                }
-                StringFragment::EscapedWS => string,
-            },
-        );
-
-        parser::delimited(parser::char(start), parse_raw_string, parser::char(end))(input)
+        parser::delimited(parser::char(start), fragments(parse_literal), parser::char(end))(input)
     }
-}
\ No newline at end of file
+}
+
+/// Parses the text of a template string up to its closing backtick or the `${` of an embedded value. A `$` which
+/// doesn't open a value is kept as it is.
+pub(crate) fn parse_template_text(input: &str) -> IResult<&str, String> {
+    let parse_literal = parser::alt((
+        parser::verify(parser::is_not("`\\$"), |s: &str| !s.is_empty()),
+        parser::recognize(parser::terminated(parser::char('$'), parser::not(parser::char('{')))),
+    ));
+
+    fragments(parse_literal)(input)
+}
+
+/// Joins runs of literal text and escape sequences into a single string.
+fn fragments<'a>(parse_literal: impl FnMut(&'a str) -> IResult<&'a str, &'a str>) -> impl FnMut(&'a str) -> IResult<&'a str, String> {
+    let fragment = parser::alt((
+        parser::map(parse_literal, StringFragment::Literal),
+        parser::map(parse_escaped_char, StringFragment::EscapedChar),
+        parser::value(StringFragment::EscapedWS, parse_escaped_whitespace),
+    ));
+
+    nom::multi::fold_many0(
+        fragment,
+        String::new,
+        |mut string, fragment| match fragment {
+            StringFragment::Literal(lit) => {
+                string.push_str(lit);
+                string
+            }
+            StringFragment::EscapedChar(c) => {
+                string.push(c);
+                string
+            }
+            StringFragment::EscapedWS => string,
+        },
+    )
+}
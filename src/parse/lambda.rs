@@ -0,0 +1,53 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use nom::IResult;
+use crate::{
+    parse::error::{expect, Expected},
+    parse::key::parse_name,
+    parse::parser,
+    parse::pattern::Pattern,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::open_value_parser,
+    parse::value::Value,
+    parse::whitespace::token,
+};
+
+/// An anonymous function. Evaluating a lambda produces a function object which captures the bindings visible where
+/// it was written.
+#[derive(Debug, PartialEq)]
+pub struct Lambda {
+    /// Parameters destructure their arguments, and those with defaults may be omitted.
+    pub parameters: Rc<[Pattern]>,
+    pub body: Rc<Value>,
+    pub span: Span,
+}
+
+impl Lambda {
+    /// name => value | ( pattern, ... ) => value
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let (remaining, (parameters, _, body)) = parser::tuple((
+            parser::alt((
+                parser::map(parse_name, |name| alloc::vec![Pattern::Name(name)]),
+                |input| Lambda::parameters(input, &cx),
+            )),
+            token(parser::tag("=>")),
+            expect(&cx, Expected::Operand("=>"), open_value_parser(cx.clone())),
+        ))(input)?;
+
+        Ok((remaining, Lambda {
+            parameters: parameters.into(),
+            body: Rc::new(body),
+            span: cx.span(input, remaining),
+        }))
+    }
+
+    /// ( pattern, * )
+    pub(super) fn parameters<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Vec<Pattern>> {
+        parser::delimited(
+            parser::char('('),
+            parser::separated_list0(token(parser::char(',')), |input| Pattern::element(input, cx)),
+            token(parser::char(')')),
+        )(input)
+    }
+}
@@ -1,19 +1,29 @@
 use alloc::vec::Vec;
 use nom::IResult;
 use crate::{
+    parse::error::closing,
     parse::ParseContext,
     parse::parser,
-    parse::value::value_parser,
-    parse::value::Value
+    parse::span::Span,
+    parse::spread::Spread,
+    parse::value::Value,
+    parse::whitespace::token,
 };
 
 #[derive(Debug, PartialEq)]
 pub struct List {
-    pub items: Vec<Value>
+    /// Items may be spreads, whose contents are unpacked in their place.
+    pub items: Vec<Value>,
+    pub span: Span,
 }
 
 impl List {
     pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
-        parser::map(parser::delimited(parser::char('['), parser::separated_list0(parser::char(','), value_parser(cx)), parser::char(']')), |items| List { items })(input)
+        let (remaining, items) = parser::delimited(parser::char('['), parser::separated_list0(token(parser::char(',')), Spread::or_value(cx.clone())), closing(&cx, ']', "list", input))(input)?;
+
+        Ok((remaining, List {
+            items,
+            span: cx.span(input, remaining),
+        }))
     }
 }
\ No newline at end of file
@@ -1,9 +1,8 @@
-use crate::parse::key::Key;
+use crate::parse::key::{keyword, Key};
 use crate::parse::parser;
 use alloc::borrow::ToOwned;
 use alloc::format;
 use alloc::string::String;
-use alloc::vec::Vec;
 use nom::IResult;
 
 #[derive(Debug, PartialEq)]
@@ -28,9 +27,9 @@ impl From<Key> for Literal {
 impl Literal {
     pub fn parse(input: &str) -> IResult<&str, Self> { // TODO: pub(super)
         parser::alt((
-            parser::map(parser::tag("nothing"), |_| Literal::Nothing),
-            parser::map(parser::tag("true"), |_| Literal::Bool(true)),
-            parser::map(parser::tag("false"), |_| Literal::Bool(false)),
+            parser::map(keyword("nothing"), |_| Literal::Nothing),
+            parser::map(keyword("true"), |_| Literal::Bool(true)),
+            parser::map(keyword("false"), |_| Literal::Bool(false)),
             parse_address,
             parse_number,
             parser::map(Key::parse, Literal::from),
@@ -40,14 +39,14 @@ impl Literal {
 
 fn parse_number(input: &str) -> IResult<&str, Literal> {
     parser::map(
+        // Longer forms first, since every one of them starts with something `parse_integer` would accept.
         parser::alt((
-            parse_integer,
-            parse_decimal,
-            // These can fail fast because they have tags, however they are not used nearly as often as the above two.
             parse_hex,
             parse_oct,
             parse_bin,
             parse_scientific,
+            parse_decimal,
+            parse_integer,
         )),
         Literal::Number,
     )(input)
@@ -64,70 +63,33 @@ fn negative(input: &str) -> IResult<&str, &str> {
 }
 
 fn parse_hex(input: &str) -> IResult<&str, f64> {
-    parser::map(
-        parser::tuple((
-            negative,
-            parser::tag("0x"),
-            parser::many1(parser::alt((parser::hex_digit1, parser::tag("_")))),
-        )),
-        |(neg, _, body)| {
-            // TODO: handle parse errors properly
-
-            let body = body
-                .into_iter()
-                .flat_map(|i| i.chars())
-                .filter(|i| nom::character::is_hex_digit(*i as u8))
-                .collect::<String>();
-
-            i64::from_str_radix(&format!("{}{}", neg, body), 2).unwrap() as f64
-        },
-    )(input)
+    parse_radix(input, "0x", 16)
 }
 
 fn parse_oct(input: &str) -> IResult<&str, f64> {
-    parser::map(
-        parser::tuple((
-            negative,
-            parser::tag("0o"),
-            parser::many1(parser::alt((parser::oct_digit1, parser::tag("_")))),
-        )),
-        |(neg, _, body)| {
-            // TODO: handle parse errors properly
-
-            let body = body
-                .into_iter()
-                .flat_map(|i| i.chars())
-                .filter(|i| nom::character::is_oct_digit(*i as u8))
-                .collect::<String>();
-
-            i64::from_str_radix(&format!("{}{}", neg, body), 2).unwrap() as f64
-        },
-    )(input)
+    parse_radix(input, "0o", 8)
 }
 
 fn parse_bin(input: &str) -> IResult<&str, f64> {
-    parser::map(
-        parser::tuple((
-            negative,
-            parser::tag("0b"),
-            parser::many1(parser::alt((
-                parser::char('1'),
-                parser::char('0'),
-                parser::char('_'),
-            ))),
-        )),
-        |(neg, _, body): (&str, &str, Vec<char>)| {
-            // TODO: handle parse errors properly
+    parse_radix(input, "0b", 2)
+}
 
-            let body = neg
-                .chars()
-                .chain(body.into_iter())
-                .filter(|i| *i != '_')
-                .collect::<String>();
+/// An integer written in `radix` after `prefix`, whose digits may be separated by underscores. Literals which don't
+/// fit in 64 bits fail outright rather than being read as something else.
+fn parse_radix<'a>(input: &'a str, prefix: &'static str, radix: u32) -> IResult<&'a str, f64> {
+    let (remaining, (neg, _, body)) = parser::tuple((
+        negative,
+        parser::tag(prefix),
+        parser::verify(
+            parser::recognize(parser::many1(parser::satisfy(|char| char == '_' || char.is_digit(radix)))),
+            |body: &str| body.chars().any(|char| char != '_'),
+        ),
+    ))(input)?;
 
-            i64::from_str_radix(&body, 2).unwrap() as f64
-        },
-    )(input)
+    let magnitude = u64::from_str_radix(&body.replace('_', ""), radix)
+        .map_err(|_| too_large(input))? as f64;
+
+    Ok((remaining, if neg.is_empty() { magnitude } else { -magnitude }))
 }
 
 fn parse_float(input: &str) -> IResult<&str, String> {
@@ -143,35 +105,35 @@ fn parse_float(input: &str) -> IResult<&str, String> {
 }
 
 fn parse_decimal(input: &str) -> IResult<&str, f64> {
-    parser::map(parse_float, |float| float.parse().unwrap())(input)
+    let (remaining, float) = parse_float(input)?;
+    Ok((remaining, float_value(input, &float)?))
 }
 
 fn parse_scientific(input: &str) -> IResult<&str, f64> {
-    parser::map(
-        parser::tuple((parse_float, parser::tag_no_case("e"), parse_float)),
-        |(base, _, exponent)| {
-            // TODO: handle parse errors properly
-            let base = base.parse::<f64>().unwrap();
-            let exponent = exponent.parse::<f64>().unwrap();
-
-            base * 10.0f64.powf(exponent)
-        },
-    )(input)
+    let (remaining, number) = parser::recognize(parser::tuple((
+        parser::alt((parser::map(parse_float, |_| ()), parser::map(parse_integer, |_| ()))),
+        parser::tag_no_case("e"),
+        parser::opt(parser::one_of("+-")),
+        parser::digit1,
+    )))(input)?;
+
+    // Read as a whole, since computing `base * 10^exponent` rounds twice and turns `4.2e-1` into `0.42000000000000004`
+    Ok((remaining, float_value(input, &number.replace('_', ""))?))
 }
 
+/// Decimal integers are read as floats directly, so those beyond 64 bits lose precision instead of failing.
 fn parse_integer(input: &str) -> IResult<&str, f64> {
-    parser::map(parser::tuple((negative, parser::digit1)), |(neg, num)| {
-        num.parse::<i64>()
-            .map(|i| {
-                if neg.len() > 0 {
-                    (i * -1) as f64
-                } else {
-                    i as f64
-                }
-            })
-            // TODO: Handle errors properly
-            .unwrap()
-    })(input)
+    let (remaining, number) = parser::recognize(parser::pair(negative, parser::digit1))(input)?;
+    Ok((remaining, float_value(input, number)?))
+}
+
+fn float_value<'a>(input: &'a str, number: &str) -> Result<f64, nom::Err<nom::error::Error<&'a str>>> {
+    number.parse().map_err(|_| too_large(input))
+}
+
+/// Fails on a number literal starting at `input` which can't be represented.
+fn too_large(input: &str) -> nom::Err<nom::error::Error<&str>> {
+    nom::Err::Failure(nom::error::Error::new(input, nom::error::ErrorKind::TooLarge))
 }
 
 #[derive(Debug, Clone, PartialEq)]
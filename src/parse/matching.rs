@@ -0,0 +1,47 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use nom::IResult;
+use crate::{
+    parse::error::{closing, expect, Expected},
+    parse::key::keyword,
+    parse::parser,
+    parse::pattern::Pattern,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::{value_parser, Value},
+    parse::whitespace::{token, whitespace},
+};
+
+/// Chooses the first arm whose pattern matches a value, evaluating it with the names the pattern binds.
+#[derive(Debug, PartialEq)]
+pub struct Match {
+    pub value: Box<Value>,
+    pub arms: Vec<(Pattern, Value)>,
+    pub span: Span,
+}
+
+impl Match {
+    /// match value { pattern => value, * }
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let arm = |input| parser::separated_pair(
+            |input| Pattern::parse(input, &cx),
+            expect(&cx, Expected::Token("=>"), token(parser::tag("=>"))),
+            expect(&cx, Expected::Operand("=>"), value_parser(cx.clone())),
+        )(input);
+
+        let (remaining, (_, value)) = parser::pair(keyword("match"), value_parser(cx.clone()))(input)?;
+        let (opening, _) = whitespace(remaining)?;
+
+        let (remaining, arms) = parser::delimited(
+            expect(&cx, Expected::Token("{"), parser::char('{')),
+            parser::separated_list1(token(parser::char(',')), arm),
+            parser::pair(parser::opt(token(parser::char(','))), closing(&cx, '}', "match", opening)),
+        )(opening)?;
+
+        Ok((remaining, Match {
+            value: Box::new(value),
+            arms,
+            span: cx.span(input, remaining),
+        }))
+    }
+}
@@ -5,25 +5,41 @@ pub mod list;
 pub mod literal;
 pub mod associative_array;
 pub mod expression;
+pub mod conditional;
+pub mod lambda;
+pub mod binding;
+pub mod pattern;
+pub mod matching;
+pub mod pipeline;
+pub mod program;
+pub mod spread;
+pub mod template;
 pub mod value;
+pub mod span;
+pub mod error;
+pub(crate) mod whitespace;
 pub mod test;
 
 use crate::{
     error::*,
+    parse::error::{Expected, Furthest},
+    parse::key::is_name_char,
+    parse::expression::{Fixity, OperatorSyntax},
+    parse::whitespace::whitespace,
+    parse::span::{Source, Span},
     parse::value::value_parser,
     parse::value::Value,
+    parse::program::Program,
     Context,
     DataSource
 };
 use alloc::{
-    borrow::ToOwned,
-    collections::BTreeMap,
     rc::Rc,
-    string::String,
-    vec,
     vec::Vec
 };
+use core::cell::{Cell, RefCell};
 use core::ops::Deref;
+use nom::IResult;
 
 pub(super) mod parser {
     pub use nom::branch::*;
@@ -35,28 +51,175 @@ pub(super) mod parser {
 }
 
 pub(crate) mod objects {
-    pub(crate) use crate::parse::access::Access;
+    pub(crate) use crate::parse::access::{Access, Index};
+    pub(crate) use crate::parse::associative_array::Entry;
+    pub(crate) use crate::parse::binding::Let;
     pub(crate) use crate::parse::call::Call;
+    pub(crate) use crate::parse::conditional::Conditional;
     pub(crate) use crate::parse::expression::Expression;
+    pub(crate) use crate::parse::expression::Associativity;
+    pub(crate) use crate::parse::expression::Fixity;
     pub(crate) use crate::parse::key::Key;
+    pub(crate) use crate::parse::lambda::Lambda;
     pub(crate) use crate::parse::literal::Literal;
+    pub(crate) use crate::parse::matching::Match;
+    pub(crate) use crate::parse::pattern::Pattern;
+    pub(crate) use crate::parse::pipeline::Pipeline;
+    pub(crate) use crate::parse::program::Statement;
+    pub(crate) use crate::parse::spread::Spread;
+    pub(crate) use crate::parse::template::{Segment, Template};
     pub(crate) use crate::parse::value::Value;
 }
 
 pub struct ParseContext(Rc<ContextInner>);
 
 impl ParseContext {
-    fn new(precedences: Vec<i64>, operators: BTreeMap<i64, Vec<String>>) -> Self {
+    fn new(operators: Vec<OperatorSyntax>) -> Self {
         Self(Rc::new(ContextInner {
-            precedences,
             operators,
+            source: None,
+            furthest: RefCell::default(),
+            terminators: RefCell::default(),
+            statement: Cell::default(),
         }))
     }
-    
+
+    /// Produces a copy of this context which records spans relative to `source`.
+    fn with_source(&self, source: &str) -> Self {
+        Self(Rc::new(ContextInner {
+            operators: self.operators.clone(),
+            source: Some(Source::new(source)),
+            furthest: RefCell::default(),
+            terminators: RefCell::default(),
+            statement: Cell::default(),
+        }))
+    }
+
+    /// Matches an operator of the given `fixity` at the start of `input`, returning the input following it.
+    /// When several symbols match, the longest one wins regardless of precedence, so `<=` is never read as `<`.
+    pub(crate) fn operator<'a>(&self, input: &'a str, fixity: Fixity) -> Option<(&'a str, &OperatorSyntax)> {
+        self.operators.iter()
+            .filter(|operator| operator.fixity == fixity)
+            .filter(|operator| input.starts_with(operator.symbol.as_str()))
+            .filter(|operator| !self.terminators.borrow().contains(&operator.symbol.as_str()))
+            .filter(|operator| !operator.is_word() || !input[operator.symbol.len()..].starts_with(is_name_char))
+            .max_by_key(|operator| operator.symbol.len())
+            .map(|operator| (&input[operator.symbol.len()..], operator))
+    }
+
+    /// Runs `parser` with `word` reserved as the end of the enclosing construct rather than an operator, so the `in`
+    /// of `let x = a in b` isn't read as a membership test. Values nested within delimiters are unaffected.
+    pub(crate) fn terminated_by<'a, O>(&self, word: &'static str, parser: impl Fn(&'a str) -> IResult<&'a str, O>) -> impl Fn(&'a str) -> IResult<&'a str, O> {
+        let cx = self.clone();
+
+        move |input| {
+            cx.terminators.borrow_mut().push(word);
+            let result = parser(input);
+            cx.terminators.borrow_mut().pop();
+
+            result
+        }
+    }
+
+    /// Runs `parser` as a statement of a program. A new line starting with an operator which may be a prefix begins the
+    /// next statement rather than continuing this one, so `let a = 5\n-a` is two statements, and indices and arguments
+    /// must immediately follow the value they apply to. Values nested within delimiters are unaffected.
+    pub(crate) fn statement<'a, O>(&self, parser: impl Fn(&'a str) -> IResult<&'a str, O>) -> impl Fn(&'a str) -> IResult<&'a str, O> {
+        let cx = self.clone();
+
+        move |input| {
+            let statement = cx.statement.replace(true);
+            let result = parser(input);
+            cx.statement.set(statement);
+
+            result
+        }
+    }
+
+    /// The span covered between `input` and `remaining`, both of which must be suffixes of the source being parsed.
+    pub(crate) fn span(&self, input: &str, remaining: &str) -> Span {
+        self.source.as_ref()
+            .map(|source| source.span(input, remaining))
+            .unwrap_or_default()
+    }
+
+    /// # Parsing
+    /// Parses a complete expression. Anything other than whitespace or comments following the expression is an error.
+    ///
+    /// ```rust
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).parse_context();
+    ///
+    /// assert!(cx.parse("1 + 2 // three").is_ok());
+    /// assert!(cx.parse("1 + 2) * 3").is_err());
+    /// ```
     pub fn parse(&self, expression: impl AsRef<str>) -> Result<Value> {
-        value_parser(self.clone())(expression.as_ref())
-            .map(|(_, v)| v)
-            .map_err(|err| stringify(err).into())
+        let expression = expression.as_ref();
+        let cx = self.with_source(expression);
+
+        let (remaining, value) = cx.parse_value(expression)?;
+        let remaining = whitespace(remaining).map_or(remaining, |(remaining, _)| remaining);
+
+        if remaining.is_empty() {
+            Ok(value)
+        } else {
+            cx.record(remaining, Expected::End);
+            Err(cx.error(remaining).into())
+        }
+    }
+
+    /// # Prefix Parsing
+    /// Parses an expression from the start of `expression`, returning it along with the unconsumed remainder.
+    /// This allows expressions to be embedded inside other text formats, which decide for themselves what may follow.
+    ///
+    /// ```rust
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).parse_context();
+    /// let (_, remaining) = cx.parse_prefix("1 + 2 }} trailing text").unwrap();
+    ///
+    /// assert_eq!(remaining, " }} trailing text");
+    /// ```
+    pub fn parse_prefix<'a>(&self, expression: &'a str) -> Result<(Value, &'a str)> {
+        let (remaining, value) = self.with_source(expression).parse_value(expression)?;
+        Ok((value, remaining))
+    }
+
+    /// # Program Parsing
+    /// Parses a sequence of statements separated by `;` or new lines. A statement is an expression, a binding such as
+    /// `let rate = 0.2` or a function definition such as `let tax(x) = x * rate`, and may span several lines as long
+    /// as each continuation starts with an infix operator which can't also be a prefix, such as `+` but not `-`.
+    ///
+    /// ```rust
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    ///
+    /// let cx = Context::new(EmptyProvider::new()).parse_context();
+    ///
+    /// assert_eq!(cx.parse_program("let x = 1\nlet double(n) = n * 2; double(x)").unwrap().statements.len(), 3);
+    /// assert!(cx.parse_program("let x = 1 x").is_err());
+    /// ```
+    pub fn parse_program(&self, program: impl AsRef<str>) -> Result<Program> {
+        let program = program.as_ref();
+        let cx = self.with_source(program);
+
+        Program::parse(program, cx.clone())
+            .map(|(_, program)| program)
+            .map_err(|err| cx.nom_error(err))
+    }
+
+    fn parse_value<'a>(&self, input: &'a str) -> Result<(&'a str, Value)> {
+        value_parser(self.clone())(input).map_err(|err| self.nom_error(err))
+    }
+
+    fn nom_error(&self, err: nom::Err<nom::error::Error<&str>>) -> Error {
+        match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => self.error(err.input).into(),
+            nom::Err::Incomplete(_) => self.error("").into(),
+        }
     }
 }
 
@@ -75,45 +238,37 @@ impl Clone for ParseContext {
 }
 
 pub struct ContextInner {
-    operators: BTreeMap<i64, Vec<String>>,
-    precedences: Vec<i64>,
+    /// Sorted by precedence
+    operators: Vec<OperatorSyntax>,
+    source: Option<Source>,
+    furthest: RefCell<Furthest>,
+    /// Words which currently end a value instead of continuing it as an operator.
+    terminators: RefCell<Vec<&'static str>>,
+    /// Whether the value being parsed is a statement of a program, which new lines may end.
+    statement: Cell<bool>,
 }
 
 impl<Provider: DataSource> Context<Provider> {
     pub fn parse_context(&self) -> ParseContext {
-        // Group the operators by precedence into a BTreeMap so it's sorted.
-        let operators = self.operators.iter()
-            .fold(BTreeMap::new(), |mut accumulator, (token, operator)| {
-                if !accumulator.contains_key(&operator.precedence) {
-                    accumulator.insert(operator.precedence, vec![]);
-                }
-
-                accumulator.get_mut(&operator.precedence).unwrap().push(token.clone());
+        let mut operators = self.operators.values()
+            .map(|operator| operator.syntax())
+            .collect::<Vec<_>>();
 
-                return accumulator;
-            });
+        // Sort the operators by precedence so lookups are deterministic.
+        operators.sort_by(|a, b| (a.precedence, &a.symbol, a.fixity).cmp(&(b.precedence, &b.symbol, b.fixity)));
 
-        ParseContext::new(
-            operators.keys().copied().collect::<Vec<_>>(),
-            operators,
-        )
+        ParseContext::new(operators)
     }
 
     pub fn parse(&self, str: &str) -> Result<Value> {
         self.parse_context().parse(str)
     }
-}
 
-fn stringify(err: nom::Err<nom::error::Error<&str>>) -> nom::Err<nom::error::Error<String>> {
-    match err {
-        nom::Err::Error(err) => nom::Err::Error(nom::error::Error {
-            input: err.input.to_owned(),
-            code: err.code,
-        }),
-        nom::Err::Failure(err) => nom::Err::Failure(nom::error::Error {
-            input: err.input.to_owned(),
-            code: err.code,
-        }),
-        nom::Err::Incomplete(needed) => nom::Err::Incomplete(needed),
-    }.into()
-}
\ No newline at end of file
+    pub fn parse_prefix<'a>(&self, str: &'a str) -> Result<(Value, &'a str)> {
+        self.parse_context().parse_prefix(str)
+    }
+
+    pub fn parse_program(&self, str: &str) -> Result<Program> {
+        self.parse_context().parse_program(str)
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use nom::IResult;
+use crate::{
+    parse::error::{closing, expect, Expected},
+    parse::key::{keyword, parse_name, Key},
+    parse::literal::Literal,
+    parse::parser,
+    parse::ParseContext,
+    parse::value::{value_parser, Value},
+    parse::whitespace::{token, whitespace},
+};
+
+/// The types a pattern may test for, as named by [`Object::datatype`](crate::eval::Object::datatype).
+const DATATYPES: [&str; 7] = ["number", "string", "boolean", "list", "associative_array", "function", "range"];
+
+/// Describes the shape of a value, naming the parts of it to be bound when it matches.
+#[derive(Debug, PartialEq)]
+pub enum Pattern {
+    /// `_` matches anything without binding it.
+    Wildcard,
+    /// Matches anything, binding it to the name.
+    Name(String),
+    /// Matches a value equal to the literal, such as `1`, `'text'`, `true` or `nothing`.
+    Literal(Literal),
+    /// Matches any value of a type, such as `number _` or `string s`, binding it unless the name is `_`.
+    Type {
+        datatype: String,
+        name: Option<String>,
+    },
+    /// Matches a list or range item by item. Without a `...rest` pattern for the remaining items, the list may not
+    /// have any more items than there are patterns.
+    List {
+        items: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// Matches an associative array containing at least the given keys. A `...rest` pattern receives the entries
+    /// which weren't named.
+    AssociativeArray {
+        entries: Vec<(Key, Pattern)>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// Used in place of an item or argument which is missing or `nothing`, as in `[a, b ?? 0]`.
+    Default {
+        pattern: Box<Pattern>,
+        default: Value,
+    },
+}
+
+impl Pattern {
+    /// _ | datatype (name | _) | literal | name | [ element, * (, ...pattern)? ] | [ key = element, * (, ...pattern)? ]
+    pub(super) fn parse<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Self> {
+        let (input, _) = whitespace(input)?;
+
+        parser::alt((
+            parser::map(keyword("_"), |_| Pattern::Wildcard),
+            |input| Pattern::datatype(input),
+            parser::map(Pattern::literal, Pattern::Literal),
+            parser::map(parse_name, Pattern::Name),
+            |input| Pattern::associative_array(input, cx),
+            |input| Pattern::list(input, cx),
+        ))(input).inspect_err(|_| cx.record(input, Expected::Pattern))
+    }
+
+    /// A pattern which may be given a default, as the items of a list pattern and the parameters of a lambda may.
+    pub(super) fn element<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Self> {
+        let (remaining, pattern) = Pattern::parse(input, cx)?;
+
+        let Ok((after_operator, _)) = token(parser::tag("??"))(remaining) else {
+            return Ok((remaining, pattern));
+        };
+
+        let (remaining, default) = expect(cx, Expected::Operand("??"), value_parser(cx.clone()))(after_operator)?;
+
+        Ok((remaining, Pattern::Default {
+            pattern: Box::new(pattern),
+            default,
+        }))
+    }
+
+    /// ... pattern
+    fn rest<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Box<Self>> {
+        parser::map(
+            parser::preceded(token(parser::tag("...")), |input| Pattern::parse(input, cx)),
+            Box::new,
+        )(input)
+    }
+
+    /// The name a parameter may be given by, as for `f(x = 1)`.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Pattern::Name(name) => Some(name),
+            Pattern::Default { pattern, .. } => pattern.name(),
+            _ => None,
+        }
+    }
+
+    /// The default values within the pattern, which are evaluated when the pattern is matched.
+    pub fn defaults(&self) -> Vec<&Value> {
+        match self {
+            Pattern::Default { pattern, default } => core::iter::once(default).chain(pattern.defaults()).collect(),
+            Pattern::List { items, rest } => items.iter()
+                .chain(rest.as_deref())
+                .flat_map(Pattern::defaults)
+                .collect(),
+            Pattern::AssociativeArray { entries, rest } => entries.iter()
+                .map(|(_, pattern)| pattern)
+                .chain(rest.as_deref())
+                .flat_map(Pattern::defaults)
+                .collect(),
+            Pattern::Wildcard | Pattern::Name(_) | Pattern::Literal(_) | Pattern::Type { .. } => Vec::new(),
+        }
+    }
+
+    /// A datatype must be followed by a name or `_`, so parameters called `list` are still names.
+    fn datatype(input: &str) -> IResult<&str, Self> {
+        let (remaining, datatype) = DATATYPES.iter()
+            .find_map(|datatype| keyword(datatype)(input).ok())
+            .ok_or(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)))?;
+        let (remaining, _) = whitespace(remaining)?;
+        let (remaining, name) = parser::alt((
+            parser::map(keyword("_"), |_| None),
+            parser::map(parse_name, Some),
+        ))(remaining)?;
+
+        Ok((remaining, Pattern::Type {
+            datatype: datatype.into(),
+            name,
+        }))
+    }
+
+    /// Literals other than names and addresses, which would be read as bindings and queries.
+    fn literal(input: &str) -> IResult<&str, Literal> {
+        parser::verify(Literal::parse, |literal| !matches!(literal, Literal::Name(_) | Literal::Address(_)))(input)
+    }
+
+    fn list<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Self> {
+        let (remaining, items) = parser::preceded(
+            parser::char('['),
+            parser::separated_list0(token(parser::char(',')), |input| Pattern::element(input, cx)),
+        )(input)?;
+
+        let (remaining, rest) = parser::terminated(
+            parser::opt(parser::preceded(token(parser::tag(if items.is_empty() { "" } else { "," })), |input| Pattern::rest(input, cx))),
+            closing(cx, ']', "list pattern", input),
+        )(remaining)?;
+
+        Ok((remaining, Pattern::List { items, rest }))
+    }
+
+    fn associative_array<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Self> {
+        let entry = |input| parser::separated_pair(
+            token(Key::parse),
+            expect(cx, Expected::Token("="), token(parser::char('='))),
+            |input| Pattern::element(input, cx),
+        )(input);
+
+        let (remaining, (entries, rest)) = parser::delimited(
+            parser::char('['),
+            parser::pair(
+                parser::separated_list1(token(parser::char(',')), entry),
+                parser::opt(parser::preceded(token(parser::char(',')), |input| Pattern::rest(input, cx))),
+            ),
+            closing(cx, ']', "associative array pattern", input),
+        )(input)?;
+
+        Ok((remaining, Pattern::AssociativeArray { entries, rest }))
+    }
+}
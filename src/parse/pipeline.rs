@@ -0,0 +1,35 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::parse::call::Call;
+use crate::parse::span::Span;
+use crate::parse::value::Value;
+
+/// Feeds a value into a function as its first argument, so `x |> round(2)` is `round(x, 2)` and `x |> f` is `f(x)`.
+#[derive(Debug, PartialEq)]
+pub struct Pipeline {
+    pub value: Box<Value>,
+    pub function: Box<Value>,
+    /// The arguments following the piped value.
+    pub arguments: Vec<Value>,
+    pub named: Vec<(String, Value)>,
+    pub span: Span,
+}
+
+impl Pipeline {
+    /// Pipes `value` into `stage`, which is a call to receive it as its first argument or else a function.
+    pub(super) fn new(value: Value, stage: Value, span: Span) -> Self {
+        let (function, arguments, named) = match stage {
+            Value::Call(Call { name, arguments, named, optional: false, .. }) => (name, arguments, named),
+            stage => (Box::new(stage), Vec::new(), Vec::new()),
+        };
+
+        Self {
+            value: Box::new(value),
+            function,
+            arguments,
+            named,
+            span,
+        }
+    }
+}
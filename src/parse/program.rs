@@ -0,0 +1,129 @@
+use alloc::string::String;
+use alloc::rc::Rc;
+use alloc::vec;
+use alloc::vec::Vec;
+use nom::IResult;
+use crate::{
+    parse::error::{expect, Expected},
+    parse::key::{keyword, parse_name},
+    parse::lambda::Lambda,
+    parse::parser,
+    parse::pattern::Pattern,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::{open_value_parser, Value},
+    parse::whitespace::{token, whitespace},
+};
+
+/// A sequence of statements evaluated in order, such as the helpers of a formula followed by its result.
+#[derive(Debug, PartialEq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Statement {
+    /// `let pattern = value`, visible to every statement after it.
+    Binding(Pattern, Value),
+    /// `let name(parameters) = body`, which may call itself.
+    Function {
+        name: String,
+        lambda: Lambda,
+    },
+    Expression(Value),
+}
+
+impl Program {
+    /// statement ((; | newline) statement)*
+    ///
+    /// A line starting with an infix operator continues the statement before it, so `1\n* 2` is `1 * 2`, unless the
+    /// operator may also be a prefix: `1\n-2` is two statements.
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let mut statements = Vec::new();
+        let (mut remaining, _) = whitespace(input)?;
+
+        while !remaining.is_empty() {
+            let (after_statement, statement) = cx.statement(|input| Statement::parse(input, &cx))(remaining)?;
+            statements.extend(statement);
+
+            let (after_separator, _) = expect(&cx, Expected::Separator, separator)(after_statement)
+                .map_err(|_| nom::Err::Failure(nom::error::Error::new(after_statement, nom::error::ErrorKind::Char)))?;
+
+            // Empty statements are skipped, so a program may end with a `;`
+            (remaining, _) = parser::many0_count(token(parser::char(';')))(after_separator)?;
+            (remaining, _) = whitespace(remaining)?;
+        }
+
+        Ok((remaining, Program {
+            statements,
+            span: cx.span(input, remaining),
+        }))
+    }
+}
+
+impl Statement {
+    /// let name(parameters) = value | let pattern = value (; pattern = value)* | value
+    fn parse<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Vec<Self>> {
+        let function = |input: &'a str| {
+            let (remaining, (_, name, parameters, _, body)) = parser::tuple((
+                keyword("let"),
+                token(parse_name),
+                |input| Lambda::parameters(input, cx),
+                |input| equals(input, cx),
+                expect(cx, Expected::Operand("="), open_value_parser(cx.clone())),
+            ))(input)?;
+
+            Ok((remaining, Statement::Function {
+                name,
+                lambda: Lambda {
+                    parameters: parameters.into(),
+                    body: Rc::new(body),
+                    span: cx.span(input, remaining),
+                },
+            }))
+        };
+
+        // Further bindings may follow a `;` as in `let x = 1; y = 2`, while a `let` with a body of its own is an
+        // expression instead, as in `let x = 1 in x`.
+        let binding = |input| parser::separated_pair(
+            |input| Pattern::parse(input, cx),
+            |input| equals(input, cx),
+            cx.terminated_by("in", open_value_parser(cx.clone())),
+        )(input);
+
+        let bindings = parser::map(
+            parser::tuple((
+                keyword("let"),
+                binding,
+                parser::many0(parser::preceded(token(parser::char(';')), binding)),
+                parser::not(token(keyword("in"))),
+            )),
+            |(_, first, rest, _)| core::iter::once(first).chain(rest)
+                .map(|(pattern, value)| Statement::Binding(pattern, value))
+                .collect(),
+        );
+
+        parser::alt((
+            parser::map(function, |function| vec![function]),
+            bindings,
+            parser::map(open_value_parser(cx.clone()), |value| vec![Statement::Expression(value)]),
+        ))(input)
+    }
+}
+
+/// `=`, but not `==` or `=>`.
+fn equals<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, char> {
+    expect(cx, Expected::Token("="), token(|input| parser::terminated(parser::char('='), parser::not(parser::one_of("=>")))(input)))(input)
+}
+
+/// The end of a statement, which is a `;`, a new line or the end of the input.
+fn separator(input: &str) -> IResult<&str, ()> {
+    let (remaining, _) = whitespace(input)?;
+
+    if remaining.is_empty() || input[..input.len() - remaining.len()].contains('\n') {
+        return Ok((remaining, ()));
+    }
+
+    parser::value((), parser::char(';'))(remaining)
+}
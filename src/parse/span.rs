@@ -0,0 +1,134 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+use nom::IResult;
+use crate::parse::whitespace::whitespace;
+use crate::parse::ParseContext;
+
+/// A position within the source of an expression.
+///
+/// `offset` is a byte offset into the source, while `line` and `column` are 1-based and count characters,
+/// making them suitable for display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Location {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// # Span
+/// The region of the source an AST node was parsed from.
+///
+/// Nodes of the syntax tree compare their spans along with their contents, so the same expression parsed from
+/// differently formatted sources doesn't compare equal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    /// The byte range of the source covered by this span.
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+
+    /// Whether the byte `offset` falls within this span. The end of the span is inclusive, so a cursor placed
+    /// directly after a node is still considered to be on it.
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start.offset <= offset && offset <= self.end.offset
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.offset - self.start.offset
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A node which does not carry a span of its own, paired with the span it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+
+    /// Runs `parser` after skipping leading whitespace, recording the span of whatever it consumed.
+    pub(crate) fn parse<'a>(input: &'a str, cx: &ParseContext, parser: impl Fn(&'a str) -> IResult<&'a str, T>) -> IResult<&'a str, Self> {
+        let (input, _) = whitespace(input)?;
+        let (remaining, node) = parser(input)?;
+
+        Ok((remaining, Self::new(node, cx.span(input, remaining))))
+    }
+}
+
+impl<T> From<T> for Spanned<T> {
+    fn from(node: T) -> Self {
+        Self::new(node, Span::default())
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+/// Maps byte offsets of the expression being parsed to line/column locations.
+///
+/// Parsers only ever see suffixes of the original input, so an offset is recovered from the length of the remaining
+/// input rather than from pointer arithmetic.
+pub(crate) struct Source {
+    text: String,
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    pub(crate) fn new(text: impl AsRef<str>) -> Self {
+        let text = text.as_ref();
+
+        Self {
+            text: text.into(),
+            line_starts: core::iter::once(0)
+                .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Converts the remaining input at some point during parsing into a location in the source.
+    pub(crate) fn locate_remaining(&self, remaining: &str) -> Location {
+        self.locate(self.text.len().saturating_sub(remaining.len()))
+    }
+
+    pub(crate) fn locate(&self, offset: usize) -> Location {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        Location {
+            offset,
+            line: line + 1,
+            column: self.text[line_start..offset].chars().count() + 1,
+        }
+    }
+
+    pub(crate) fn span(&self, input: &str, remaining: &str) -> Span {
+        Span {
+            start: self.locate_remaining(input),
+            end: self.locate_remaining(remaining),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use alloc::boxed::Box;
+use nom::IResult;
+use crate::{
+    parse::error::{expect, Expected},
+    parse::parser,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::{value_parser, Value},
+    parse::whitespace::whitespace,
+};
+
+/// Unpacks the items of a list or range in place, as in `[...a, 4]` or `f(...args)`. Spreading an associative array
+/// copies its entries into another, or passes them to a call as named arguments.
+#[derive(Debug, PartialEq)]
+pub struct Spread {
+    pub value: Box<Value>,
+    pub span: Span,
+}
+
+impl Spread {
+    /// ... value
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let (input, _) = whitespace(input)?;
+        let (remaining, value) = parser::preceded(
+            parser::tag("..."),
+            expect(&cx, Expected::Operand("..."), value_parser(cx.clone())),
+        )(input)?;
+
+        Ok((remaining, Spread {
+            value: Box::new(value),
+            span: cx.span(input, remaining),
+        }))
+    }
+
+    /// Parses either a spread or a value, for the places a spread may appear.
+    pub(super) fn or_value<'a>(cx: ParseContext) -> impl Fn(&'a str) -> IResult<&'a str, Value> {
+        move |input| parser::alt((
+            parser::map(|input| Spread::parse(input, cx.clone()), Value::Spread),
+            value_parser(cx.clone()),
+        ))(input)
+    }
+}
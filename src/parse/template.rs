@@ -0,0 +1,97 @@
+use alloc::vec::Vec;
+use alloc::string::String;
+use nom::IResult;
+use crate::{
+    parse::error::{closing, expect, Expected},
+    parse::key::parse_template_text,
+    parse::parser,
+    parse::ParseContext,
+    parse::span::Span,
+    parse::value::{value_parser, Value},
+    parse::whitespace::token,
+};
+
+/// A string with values embedded in it, such as `` `Total: ${sum(items)} items` ``.
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    pub segments: Vec<Segment>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Segment {
+    Text(String),
+    /// An embedded value, optionally followed by a format for numbers, as in `${ratio:.1%}`.
+    Value {
+        value: Value,
+        format: Option<FormatSpec>,
+    },
+}
+
+/// [,][.precision][%]
+///
+/// `,` separates thousands, `.precision` fixes the number of decimal places and `%` multiplies the number by 100 and
+/// appends a percent sign. At least one of them must be given.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FormatSpec {
+    pub thousands: bool,
+    /// At most [`FormatSpec::MAX_PRECISION`].
+    pub precision: Option<usize>,
+    pub percent: bool,
+}
+
+impl FormatSpec {
+    /// The most decimal places a number may be formatted with, which keeps the text of a template bounded.
+    pub const MAX_PRECISION: usize = 100;
+
+    fn parse(input: &str) -> IResult<&str, Self> {
+        parser::verify(
+            parser::map(
+                parser::tuple((
+                    parser::opt(parser::char(',')),
+                    parser::opt(parser::preceded(parser::char('.'), parser::verify(
+                        parser::map_res(parser::digit1, str::parse),
+                        |precision| *precision <= Self::MAX_PRECISION,
+                    ))),
+                    parser::opt(parser::char('%')),
+                )),
+                |(thousands, precision, percent)| FormatSpec {
+                    thousands: thousands.is_some(),
+                    precision,
+                    percent: percent.is_some(),
+                },
+            ),
+            |spec| *spec != FormatSpec::default(),
+        )(input)
+    }
+}
+
+impl Template {
+    /// ` text ${value} text ${value:format} `
+    pub(super) fn parse(input: &str, cx: ParseContext) -> IResult<&str, Self> {
+        let (mut remaining, _) = parser::char('`')(input)?;
+        let mut segments = Vec::new();
+
+        loop {
+            let (after_text, text) = parse_template_text(remaining)?;
+            if !text.is_empty() {
+                segments.push(Segment::Text(text));
+            }
+
+            let Ok((after_placeholder, _)) = parser::tag::<_, _, nom::error::Error<&str>>("${")(after_text) else {
+                let (remaining, _) = closing(&cx, '`', "template string", input)(after_text)?;
+                return Ok((remaining, Template { segments, span: cx.span(input, remaining) }));
+            };
+
+            let (after_value, (value, format, _)) = parser::tuple((
+                value_parser(cx.clone()),
+                // Once there is a `:`, the format must be valid rather than the placeholder ending early
+                parser::opt(parser::preceded(token(parser::char(':')), parser::cut(expect(&cx, Expected::Format, FormatSpec::parse)))),
+                closing(&cx, '}', "template placeholder", after_text),
+            ))(after_placeholder)?;
+
+            segments.push(Segment::Value { value, format });
+            remaining = after_value;
+        }
+    }
+}
@@ -1,74 +1,192 @@
 #[cfg(test)]
 pub mod test {
 	use alloc::boxed::Box;
+	use alloc::rc::Rc;
 	use alloc::string::ToString;
 	use core::assert_matches::assert_matches;
 	use crate::error::*;
+	use crate::parse::binding::Let;
 	use crate::parse::call::Call;
-	use crate::parse::expression::Expression;
+	use crate::parse::conditional::Conditional;
+	use crate::parse::expression::{Associativity, Expression, Fixity, OperatorSyntax};
 	use crate::parse::key::Key;
+	use crate::parse::lambda::Lambda;
 	use crate::parse::literal::{Address, Column, Literal};
 	use crate::parse::*;
-	use crate::parse::access::Access;
-	use crate::parse::associative_array::AssociativeArray;
+	use crate::parse::access::{Access, Index};
+	use crate::parse::associative_array::{AssociativeArray, Entry};
 	use crate::parse::list::List;
-
-	/// Operators is a static map of ("Token", "Precedence", "NumOperands")
-	static OPERATORS: &'static [(&'static str, i64, u64)] = &[
-		("==", 1, 2),
-		("!=", 1, 2),
-		("&&", 3, 2),
-		("||", 3, 2),
-		("!", 3, 1),
-		(">", 5, 2),
-		("<", 5, 2),
-		("+", 10, 2),
-		("-", 10, 2),
-		("*", 15, 2),
-		("/", 15, 2),
-		("%", 15, 2),
-		("^", 20, 2),
+	use crate::parse::matching::Match;
+	use crate::parse::pattern::Pattern;
+	use crate::parse::pipeline::Pipeline;
+	use crate::parse::program::Statement;
+	use crate::parse::spread::Spread;
+	use crate::parse::template::{FormatSpec, Segment, Template};
+	use crate::parse::span::{Span, Spanned};
+
+	/// Operators is a static map of ("Token", "Precedence", "NumOperands", "Associativity")
+	static OPERATORS: &'static [(&'static str, i64, u64, Associativity)] = &[
+		("==", 4, 2, Associativity::Left),
+		("!=", 4, 2, Associativity::Left),
+		("&&", 3, 2, Associativity::Left),
+		("||", 2, 2, Associativity::Left),
+		("??", 1, 2, Associativity::Left),
+		("!", 25, 1, Associativity::Left),
+		("-", 25, 1, Associativity::Left),
+		(">", 5, 2, Associativity::Left),
+		("<", 5, 2, Associativity::Left),
+		("in", 5, 2, Associativity::Left),
+		("step", 6, 2, Associativity::Left),
+		("..", 7, 2, Associativity::None),
+		("+", 10, 2, Associativity::Left),
+		("-", 10, 2, Associativity::Left),
+		("*", 15, 2, Associativity::Left),
+		("/", 15, 2, Associativity::Left),
+		("%", 15, 2, Associativity::Left),
+		("^", 20, 2, Associativity::Right),
 	];
 
 	fn cx() -> ParseContext {
-		// Group the operators by precedence into a BTreeMap so it's sorted.
-		let operators = OPERATORS.iter()
-			.fold(BTreeMap::new(), |mut accumulator, (token, precedence, _num_operands)| {
-				if !accumulator.contains_key(precedence) {
-					accumulator.insert(*precedence, vec![]);
-				}
+		ParseContext::new(OPERATORS.iter()
+			.map(|(token, precedence, num_operands, associativity)| OperatorSyntax {
+				symbol: token.to_string(),
+				precedence: *precedence,
+				fixity: if *num_operands == 1 { Fixity::Prefix } else { Fixity::Infix },
+				associativity: *associativity,
+			})
+			.collect())
+	}
 
-				accumulator.get_mut(precedence).unwrap().push(*token);
+	fn parse(input: impl AsRef<str>) -> Result<Value> {
+		cx().parse(input)
+	}
 
-				return accumulator;
-			});
+	/// Asserts that two syntax trees have the same structure. Their spans are ignored, as they differ whenever the
+	/// source is formatted differently.
+	#[track_caller]
+	fn assert_same(mut left: Value, mut right: Value) {
+		unspan(&mut left);
+		unspan(&mut right);
 
-		ParseContext::new(
-			operators.keys().copied().collect::<Vec<_>>(),
-			operators.into_iter()
-				.map(|(precedence, tokens)| (precedence, tokens.into_iter().map(|i| i.to_owned()).collect::<Vec<_>>()))
-				.collect(),
-		)
+		assert_eq!(left, right);
 	}
 
-	fn parse(input: impl AsRef<str>) -> Result<Value> {
-		let parser = value_parser(cx());
+	/// Resets every span within `value` to the default.
+	fn unspan(value: &mut Value) {
+		match value {
+			Value::Expression(expression) => {
+				expression.span = Span::default();
+				expression.operands.iter_mut().for_each(unspan);
+			}
+			Value::Literal(literal) => literal.span = Span::default(),
+			Value::Call(call) => {
+				call.span = Span::default();
+				unspan(&mut call.name);
+				call.arguments.iter_mut().for_each(unspan);
+				call.named.iter_mut().for_each(|(_, value)| unspan(value));
+			}
+			Value::Access(access) => {
+				access.span = Span::default();
+				access.member.span = Span::default();
+				unspan(&mut access.left);
+			}
+			Value::List(list) => {
+				list.span = Span::default();
+				list.items.iter_mut().for_each(unspan);
+			}
+			Value::AssociativeArray(array) => {
+				array.span = Span::default();
+				array.items.iter_mut().for_each(|entry| match entry {
+					Entry::Pair(_, value) => unspan(value),
+					Entry::Spread(spread) => {
+						spread.span = Span::default();
+						unspan(&mut spread.value);
+					}
+				});
+			}
+			Value::Conditional(conditional) => {
+				conditional.span = Span::default();
+				unspan(&mut conditional.condition);
+				unspan(&mut conditional.then);
+				unspan(&mut conditional.otherwise);
+			}
+			Value::Lambda(lambda) => {
+				lambda.span = Span::default();
+				Rc::get_mut(&mut lambda.parameters).expect("Parsed lambdas are not shared").iter_mut().for_each(unspan_pattern);
+				unspan(Rc::get_mut(&mut lambda.body).expect("Parsed lambdas are not shared"));
+			}
+			Value::Let(binding) => {
+				binding.span = Span::default();
+				binding.bindings.iter_mut().for_each(|(pattern, value)| {
+					unspan_pattern(pattern);
+					unspan(value);
+				});
+				unspan(&mut binding.body);
+			}
+			Value::Index(index) => {
+				index.span = Span::default();
+				unspan(&mut index.left);
+				unspan(&mut index.index);
+			}
+			Value::Pipeline(pipeline) => {
+				pipeline.span = Span::default();
+				unspan(&mut pipeline.value);
+				unspan(&mut pipeline.function);
+				pipeline.arguments.iter_mut().for_each(unspan);
+				pipeline.named.iter_mut().for_each(|(_, value)| unspan(value));
+			}
+			Value::Template(template) => {
+				template.span = Span::default();
+				template.segments.iter_mut().for_each(|segment| match segment {
+					Segment::Value { value, .. } => unspan(value),
+					Segment::Text(_) => (),
+				});
+			}
+			Value::Spread(spread) => {
+				spread.span = Span::default();
+				unspan(&mut spread.value);
+			}
+			Value::Match(matching) => {
+				matching.span = Span::default();
+				unspan(&mut matching.value);
+				matching.arms.iter_mut().for_each(|(pattern, value)| {
+					unspan_pattern(pattern);
+					unspan(value);
+				});
+			}
+		}
+	}
 
-		parser(input.as_ref())
-			.map(|(_, v)| v)
-			.map_err(|err| stringify(err).into())
+	fn unspan_pattern(pattern: &mut Pattern) {
+		match pattern {
+			Pattern::List { items, rest } => {
+				items.iter_mut().for_each(unspan_pattern);
+				rest.iter_mut().for_each(|rest| unspan_pattern(rest));
+			}
+			Pattern::AssociativeArray { entries, rest } => {
+				entries.iter_mut().for_each(|(_, pattern)| unspan_pattern(pattern));
+				rest.iter_mut().for_each(|rest| unspan_pattern(rest));
+			}
+			Pattern::Default { pattern, default } => {
+				unspan_pattern(pattern);
+				unspan(default);
+			}
+			Pattern::Wildcard | Pattern::Name(_) | Pattern::Literal(_) | Pattern::Type { .. } => (),
+		}
 	}
 
 	#[test]
 	pub fn test_extremely_simple_expression() -> Result<()> {
 		let expr = parse("1+2");
 
-		assert_eq!(expr?, Value::Expression(Expression {
+		assert_same(expr?, Value::Expression(Expression {
 			operands: vec![
-				Value::Literal(Literal::Number(1.0)),
-				Value::Literal(Literal::Number(2.0)),
+				Value::Literal(Literal::Number(1.0).into()),
+				Value::Literal(Literal::Number(2.0).into()),
 			],
-			operator: "+".to_owned()
+			operator: "+".to_owned(),
+			fixity: Fixity::Infix,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -78,51 +196,78 @@ pub mod test {
 	pub fn test_oder_of_operations() -> Result<()> {
 		let expr = parse("1*2+3^2");
 
-		assert_eq!(expr?, Value::Expression(Expression {
+		assert_same(expr?, Value::Expression(Expression {
 			operands: vec![
 				Value::Expression(Expression {
 					operands: vec![
-						Value::Literal(Literal::Number(1.0)),
-						Value::Literal(Literal::Number(2.0)),
+						Value::Literal(Literal::Number(1.0).into()),
+						Value::Literal(Literal::Number(2.0).into()),
 					],
-					operator: "*".to_owned()
+					operator: "*".to_owned(),
+					fixity: Fixity::Infix,
+					span: Span::default(),
 				}),
 				Value::Expression(Expression {
 					operands: vec![
-						Value::Literal(Literal::Number(3.0)),
-						Value::Literal(Literal::Number(2.0)),
+						Value::Literal(Literal::Number(3.0).into()),
+						Value::Literal(Literal::Number(2.0).into()),
 					],
-					operator: "^".to_owned()
+					operator: "^".to_owned(),
+					fixity: Fixity::Infix,
+					span: Span::default(),
 				})
 			],
-			operator: "+".to_owned()
+			operator: "+".to_owned(),
+			fixity: Fixity::Infix,
+			span: Span::default(),
 		}));
 
 		Ok(())
 	}
 
+	#[test]
+	pub fn test_standard_precedence() -> Result<()> {
+		let cx = crate::Context::new(crate::EmptyProvider::new()).parse_context();
+
+		assert_same(cx.parse("a == b && c")?, cx.parse("(a == b) && c")?);
+		assert_same(cx.parse("a && b != c")?, cx.parse("a && (b != c)")?);
+		assert_same(cx.parse("a == b && c || d")?, cx.parse("((a == b) && c) || d")?);
+		assert_same(cx.parse("a or b and c")?, cx.parse("a or (b and c)")?);
+		assert_same(cx.parse("a ?? b || c")?, cx.parse("a ?? (b || c)")?);
+		assert_same(cx.parse("1..n + 1")?, cx.parse("1..(n + 1)")?);
+		assert_same(cx.parse("a in 1..n step 2")?, cx.parse("a in ((1..n) step 2)")?);
+
+		Ok(())
+	}
+
 	#[test]
 	pub fn test_parentheses() -> Result<()> {
 		let expr = parse("1*(2+3)^4");
 
-		assert_eq!(expr?, Value::Expression(Expression {
+		assert_same(expr?, Value::Expression(Expression {
 			operands: vec![
-				Value::Literal(Literal::Number(1.0)),
+				Value::Literal(Literal::Number(1.0).into()),
 				Value::Expression(Expression {
 					operands: vec![
 						Value::Expression(Expression {
 							operands: vec![
-								Value::Literal(Literal::Number(2.0)),
-								Value::Literal(Literal::Number(3.0)),
+								Value::Literal(Literal::Number(2.0).into()),
+								Value::Literal(Literal::Number(3.0).into()),
 							],
-							operator: "+".to_owned()
+							operator: "+".to_owned(),
+							fixity: Fixity::Infix,
+							span: Span::default(),
 						}),
-						Value::Literal(Literal::Number(4.0)),
+						Value::Literal(Literal::Number(4.0).into()),
 					],
-					operator: "^".to_owned()
+					operator: "^".to_owned(),
+					fixity: Fixity::Infix,
+					span: Span::default(),
 				})
 			],
-			operator: "*".to_owned()
+			operator: "*".to_owned(),
+			fixity: Fixity::Infix,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -130,7 +275,7 @@ pub mod test {
 
 	#[test]
 	pub fn test_names() -> Result<()> {
-		assert_eq!(parse("a")?, Value::Literal(Literal::Name("a".to_owned())));
+		assert_same(parse("a")?, Value::Literal(Literal::Name("a".to_owned()).into()));
 		assert_matches!(Key::parse("0"), Err(_));
 		assert_matches!(Key::parse("."), Err(_));
 		assert_matches!(Key::parse(":"), Err(_));
@@ -140,14 +285,11 @@ pub mod test {
 
 	#[test]
 	pub fn test_access() -> Result<()> {
-		assert_eq!(Access::parse("a.b", cx()).unwrap().1, Access {
-			left: Box::new(Value::Literal(Literal::Name("a".into()))),
-			member: Literal::Name("b".into()),
-		});
-
-		assert_eq!(parse("a.b")?, Value::Access(Access {
-			left: Box::new(Value::Literal(Literal::Name("a".to_owned()))),
-			member: Literal::Name("b".to_string()),
+		assert_same(parse("a.b")?, Value::Access(Access {
+			left: Box::new(Value::Literal(Literal::Name("a".to_owned()).into())),
+			member: Literal::Name("b".to_string()).into(),
+			optional: false,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -170,10 +312,13 @@ pub mod test {
 
 	#[test]
 	pub fn test_call() -> Result<()> {
-		assert_eq!(parse("hello(1)")?, Value::Call(Call {
+		assert_same(parse("hello(1)")?, Value::Call(Call {
 			// name: Key::Name("hello".to_owned()),
-			name: Box::new(Value::Literal(Literal::Name("hello".to_owned()))),
-			arguments: vec![Value::Literal(Literal::Number(1.0))],
+			name: Box::new(Value::Literal(Literal::Name("hello".to_owned()).into())),
+			arguments: vec![Value::Literal(Literal::Number(1.0).into())],
+			named: vec![],
+			optional: false,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -181,12 +326,13 @@ pub mod test {
 
 	#[test]
 	pub fn test_list() -> Result<()> {
-		assert_eq!(parse("[1,2,3]")?, Value::List(List {
+		assert_same(parse("[1,2,3]")?, Value::List(List {
 			items: vec![
-				Value::Literal(Literal::Number(1.0)),
-				Value::Literal(Literal::Number(2.0)),
-				Value::Literal(Literal::Number(3.0)),
-			]
+				Value::Literal(Literal::Number(1.0).into()),
+				Value::Literal(Literal::Number(2.0).into()),
+				Value::Literal(Literal::Number(3.0).into()),
+			],
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -194,11 +340,14 @@ pub mod test {
 
 	#[test]
 	pub fn test_access_on_inline_list() -> Result<()> {
-		assert_eq!(parse("([1]).0")?, Value::Access(Access {
+		assert_same(parse("([1]).0")?, Value::Access(Access {
 			left: Box::new(Value::List(List {
-				items: vec![Value::Literal(Literal::Number(1.0))],
+				items: vec![Value::Literal(Literal::Number(1.0).into())],
+				span: Span::default(),
 			})),
-			member: Literal::Number(0.0f64)
+			member: Literal::Number(0.0f64).into(),
+			optional: false,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -206,12 +355,13 @@ pub mod test {
 
 	#[test]
 	pub fn test_associative_array() -> Result<()> {
-		assert_eq!(parse("[tomato=1,beans=2,cheese=3]")?, Value::AssociativeArray(AssociativeArray {
+		assert_same(parse("[tomato=1,beans=2,cheese=3]")?, Value::AssociativeArray(AssociativeArray {
 			items: vec![
-				(Key::Name("tomato".into()), Value::Literal(Literal::Number(1.0))),
-				(Key::Name("beans".into()), Value::Literal(Literal::Number(2.0))),
-				(Key::Name("cheese".into()), Value::Literal(Literal::Number(3.0))),
-			]
+				Entry::Pair(Key::Name("tomato".into()), Value::Literal(Literal::Number(1.0).into())),
+				Entry::Pair(Key::Name("beans".into()), Value::Literal(Literal::Number(2.0).into())),
+				Entry::Pair(Key::Name("cheese".into()), Value::Literal(Literal::Number(3.0).into())),
+			],
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -219,13 +369,16 @@ pub mod test {
 
 	#[test]
 	pub fn test_access_on_inline_object() -> Result<()> {
-		assert_eq!(parse("([x=1]).x")?, Value::Access(Access {
+		assert_same(parse("([x=1]).x")?, Value::Access(Access {
 			left: Box::new(Value::AssociativeArray(AssociativeArray {
 				items: vec![
-					(Key::Name("x".to_string()), Value::Literal(Literal::Number(1.0)))
-				].into_iter().collect()
+					Entry::Pair(Key::Name("x".to_string()), Value::Literal(Literal::Number(1.0).into()))
+				].into_iter().collect(),
+				span: Span::default(),
 			})),
-			member: Literal::Name("x".into())
+			member: Literal::Name("x".into()).into(),
+			optional: false,
+			span: Span::default(),
 		}));
 
 		Ok(())
@@ -233,14 +386,594 @@ pub mod test {
 
 	#[test]
 	pub fn test_parse_address() -> Result<()> {
-		assert_eq!(parse("{hello}")?, Value::Literal(Literal::Address(Address {
+		assert_same(parse("{hello}")?, Value::Literal(Literal::Address(Address {
 			query: "hello".to_owned()
-		})));
+		}).into()));
 
-		assert_eq!(parse("{{Hello}}")?, Value::Literal(Literal::Address(Address {
+		assert_same(parse("{{Hello}}")?, Value::Literal(Literal::Address(Address {
 			query: "{Hello}".to_owned()
-		})));
+		}).into()));
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_spans() -> Result<()> {
+		let expr = cx().parse("1+sin(2)")?;
+
+		assert_eq!(expr.span().range(), 0..8);
+		assert_eq!(expr.children().iter().map(|i| i.span().range()).collect::<Vec<_>>(), vec![0..1, 2..8]);
+
+		let Value::Call(call) = expr.children()[1] else { panic!("Expected call") };
+		assert_eq!(call.name.span().range(), 2..5);
+		assert_eq!(call.arguments[0].span().range(), 6..7);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_span_locations() -> Result<()> {
+		let expr = cx().parse("'line\nbreak'+x")?;
+		let name = expr.children()[1].span();
+
+		assert_eq!((name.start.line, name.start.column, name.start.offset), (2, 8, 13));
+		assert_eq!((name.end.line, name.end.column, name.end.offset), (2, 9, 14));
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_node_at() -> Result<()> {
+		let expr = cx().parse("[a=1,b=f(2)]")?;
+
+		assert_matches!(expr.node_at(9), Some(Value::Literal(lit)) if lit.node == Literal::Number(2.0));
+		assert_matches!(expr.node_at(7), Some(Value::Literal(lit)) if lit.node == Literal::Name("f".into()));
+		assert_matches!(expr.node_at(5), Some(Value::AssociativeArray(_)));
+		assert_matches!(expr.node_at(20), None);
+
+		// Parentheses belong to the value they enclose
+		let expr = cx().parse("2 * (x + 1)")?;
+		assert_matches!(expr.node_at(4), Some(Value::Expression(Expression { operator, span, .. })) if operator == "+" && span.range() == (4..11));
+		assert_matches!(expr.node_at(11), Some(Value::Expression(Expression { operator, .. })) if operator == "+");
+		assert_matches!(expr.node_at(3), Some(Value::Expression(Expression { operator, .. })) if operator == "*");
+
+		// Defaults of parameters and bindings are reachable
+		assert_matches!(cx().parse("(x, y ?? 5) => x + y")?.node_at(9), Some(Value::Literal(lit)) if lit.node == Literal::Number(5.0));
+		assert_matches!(cx().parse("let [a ?? 7] = b in a")?.node_at(10), Some(Value::Literal(lit)) if lit.node == Literal::Number(7.0));
+
+		Ok(())
+	}
+
+	fn parse_error(input: &str) -> crate::parse::error::ParseError {
+		match parse(input).map_err(Error::into_inner) {
+			Err(global::Inner::ParserError(err)) => err,
+			other => panic!("Expected a parse error, got {:?}", other),
+		}
+	}
+
+	#[test]
+	pub fn test_error_operand() {
+		let err = parse_error("(1+");
+
+		assert_eq!((err.location.line, err.location.column), (1, 4));
+		assert_eq!(err.message(), "expected operand after `+`, found end of input");
+		assert_eq!(err.hint.as_deref(), Some("remove the trailing `+` or add a value after it"));
+	}
+
+	#[test]
+	pub fn test_error_expected_token() {
+		let err = parse_error("[a=1,b]");
+
+		assert_eq!(err.location.column, 7);
+		assert_eq!(err.message(), "expected `=`, found `]`");
+	}
+
+	#[test]
+	pub fn test_error_unterminated() {
+		assert_eq!(parse_error("'abc").message(), "expected closing `'` for string opened at 1:1, found end of input");
+		assert_eq!(parse_error("{a:1").message(), "expected closing `}` for address opened at 1:1, found end of input");
+		assert_eq!(parse_error("(f(1,2").message(), "expected closing `)` for call opened at 1:3, found end of input");
+	}
+
+	#[test]
+	pub fn test_error_snippet() {
+		let err = parse_error("['a\nb',2");
+
+		assert_eq!(err.to_string(), [
+			"expected closing `]` for list opened at 1:1, found end of input at 2:5",
+			"  |",
+			"2 | b',2",
+			"  |     ^",
+			"hint: add a `]` to close the list",
+		].join("\n"));
+	}
+
+	#[test]
+	pub fn test_whitespace() -> Result<()> {
+		assert_same(parse(" 1 + 2 ")?, parse("1+2")?);
+		assert_same(parse("sum( 1 ,\n\t2 )")?, parse("sum(1,2)")?);
+		assert_same(parse("[ a = 1 , b = 2 ]")?, parse("[a=1,b=2]")?);
+		assert_same(parse("[\n  1,\n  2\n]")?, parse("[1,2]")?);
+		assert_same(parse("( [x = 1] ) . x")?, parse("([x=1]).x")?);
+
+		// Spans take part in comparisons, so only the structure is the same
+		assert_ne!(parse(" 1 + 2 ")?, parse("1+2")?);
+		assert_eq!(parse(" 1 + 2 ")?, parse(" 1 + 2 ")?);
+
+		assert_eq!(parse(" 1 + 2")?.span().range(), 1..6);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_comments() -> Result<()> {
+		assert_same(parse("1 + // one\n 2")?, parse("1+2")?);
+		assert_same(parse("sum(/* first */ 1, 2 /* second */)")?, parse("sum(1,2)")?);
+		assert_same(parse("4 / 2")?, parse("4/2")?);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_trailing_input() {
+		let err = parse_error("1+2)garbage");
+		assert_eq!(err.location.column, 4);
+		assert_eq!(err.message(), "expected end of input, found `)`");
+
+		assert_eq!(parse_error(r#""Hello"World""#).message(), "expected end of input, found `W`");
+		assert_eq!(parse_error("1+").message(), "expected operand after `+`, found end of input");
+		assert_matches!(parse("1 + 2 /* trailing comment */ "), Ok(_));
+	}
+
+	#[test]
+	pub fn test_parse_prefix() -> Result<()> {
+		let (value, remaining) = cx().parse_prefix("sum(1, 2) and then some")?;
+
+		assert_same(value, parse("sum(1,2)")?);
+		assert_eq!(remaining, " and then some");
+
+		let (_, remaining) = cx().parse_prefix("[1, 2]")?;
+		assert_eq!(remaining, "");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_prefix_operators() -> Result<()> {
+		assert_matches!(parse("!true")?, Value::Expression(Expression { ref operator, fixity: Fixity::Prefix, ref operands, .. }) if operator == "!" && operands.len() == 1);
+		assert_matches!(parse("-x")?, Value::Expression(Expression { ref operator, fixity: Fixity::Prefix, .. }) if operator == "-");
+
+		// Prefix operators bind tighter than the infix operator following them
+		assert_matches!(parse("-x * 2")?, Value::Expression(Expression { ref operator, fixity: Fixity::Infix, ref operands, .. })
+			if operator == "*" && matches!(operands[0], Value::Expression(Expression { fixity: Fixity::Prefix, .. })));
+
+		// A minus between two operands is still subtraction
+		assert_matches!(parse("x - 2")?, Value::Expression(Expression { ref operator, fixity: Fixity::Infix, .. }) if operator == "-");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_postfix_operators() -> Result<()> {
+		let cx = ParseContext::new(vec![
+			OperatorSyntax { symbol: "+".to_string(), precedence: 10, fixity: Fixity::Infix, associativity: Associativity::Left },
+			OperatorSyntax { symbol: "!".to_string(), precedence: 30, fixity: Fixity::Postfix, associativity: Associativity::Left },
+		]);
+
+		assert_matches!(cx.parse("5!")?, Value::Expression(Expression { ref operator, fixity: Fixity::Postfix, ref operands, .. }) if operator == "!" && operands.len() == 1);
+		assert_matches!(cx.parse("1 + 5!")?, Value::Expression(Expression { ref operator, fixity: Fixity::Infix, ref operands, .. })
+			if operator == "+" && matches!(operands[1], Value::Expression(Expression { fixity: Fixity::Postfix, .. })));
+		assert_eq!(cx.parse("5!")?.span().range(), 0..2);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_left_associativity() -> Result<()> {
+		assert_same(parse("10-2-3")?, parse("(10-2)-3")?);
+		assert_same(parse("8/4/2")?, parse("(8/4)/2")?);
+		assert_same(parse("1+2-3+4")?, parse("((1+2)-3)+4")?);
+		assert_same(parse("1-2*3-4")?, parse("(1-(2*3))-4")?);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_right_associativity() -> Result<()> {
+		assert_same(parse("2^3^2")?, parse("2^(3^2)")?);
+		assert_same(parse("1-2^3^2")?, parse("1-(2^(3^2))")?);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_non_associativity() -> Result<()> {
+		let cx = ParseContext::new(vec![
+			OperatorSyntax { symbol: "+".to_string(), precedence: 10, fixity: Fixity::Infix, associativity: Associativity::Left },
+			OperatorSyntax { symbol: "==".to_string(), precedence: 1, fixity: Fixity::Infix, associativity: Associativity::None },
+		]);
+
+		assert_matches!(cx.parse("1 == 2"), Ok(_));
+		assert_matches!(cx.parse("(1 == 2) == 3"), Ok(_));
+		assert_matches!(cx.parse("1 + 1 == 2 + 0"), Ok(_));
+
+		let Err(global::Inner::ParserError(err)) = cx.parse("1 == 2 == 3").map_err(Error::into_inner) else {
+			panic!("Expected chained non-associative operators to be rejected");
+		};
+
+		assert_eq!(err.location.column, 8);
+		assert_eq!(err.message(), "expected parentheses around the first `==`, as it cannot be chained, found `=`");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_longest_operator() -> Result<()> {
+		let syntax = |symbol: &str, precedence| OperatorSyntax { symbol: symbol.to_string(), precedence, fixity: Fixity::Infix, associativity: Associativity::Left };
+
+		// The shorter symbols are listed first and bind tighter, so they would be found first by a naive search
+		let cx = ParseContext::new(vec![syntax("<", 5), syntax("*", 15), syntax("<=", 1), syntax("<<", 2), syntax("**", 3)]);
+
+		for symbol in ["<", "<=", "<<", "*", "**"] {
+			assert_matches!(cx.parse(format!("a {} b", symbol))?, Value::Expression(Expression { ref operator, .. }) if operator == symbol);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_word_operators() -> Result<()> {
+		let syntax = |symbol: &str, precedence, fixity| OperatorSyntax { symbol: symbol.to_string(), precedence, fixity, associativity: Associativity::Left };
+		let cx = ParseContext::new(vec![syntax("and", 3, Fixity::Infix), syntax("not", 25, Fixity::Prefix), syntax("in", 5, Fixity::Infix)]);
+
+		assert_matches!(cx.parse("a and b")?, Value::Expression(Expression { ref operator, .. }) if operator == "and");
+		assert_matches!(cx.parse("not a")?, Value::Expression(Expression { ref operator, fixity: Fixity::Prefix, .. }) if operator == "not");
+		assert_matches!(cx.parse("x in (list)")?, Value::Expression(Expression { ref operator, .. }) if operator == "in");
+
+		// Word operators don't match inside names
+		assert_matches!(cx.parse("android")?, Value::Literal(Spanned { node: Literal::Name(ref name), .. }) if name == "android");
+		assert_matches!(cx.parse("notable")?, Value::Literal(Spanned { node: Literal::Name(ref name), .. }) if name == "notable");
+		assert_matches!(cx.parse("a andb"), Err(_));
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_conditional() -> Result<()> {
+		assert_matches!(parse("if x > 1 then a else b")?, Value::Conditional(Conditional { ref condition, .. })
+			if matches!(condition.as_ref(), Value::Expression(Expression { operator, .. }) if operator == ">"));
+
+		assert_same(parse("x > 1 ? a : b")?, parse("if x > 1 then a else b")?);
+		assert_same(parse("a ? b : c ? d : e")?, parse("a ? b : (c ? d : e)")?);
+		assert_same(parse("if a then 1 else 2 + 3")?, parse("if a then 1 else (2 + 3)")?);
+		assert_same(parse("[x ? 1 : 2, 3]")?, parse("[(x ? 1 : 2), 3]")?);
+
+		// Keywords are only recognised as whole words
+		assert_matches!(parse("iffy")?, Value::Literal(_));
+		assert_eq!(parse("if ifx then thenx else elsex")?.span().range(), 0..28);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_conditional_errors() {
+		assert_eq!(parse_error("if x then 1").message(), "expected `else`, found end of input");
+		assert_eq!(parse_error("x ? 1").message(), "expected `:`, found end of input");
+		assert_eq!(parse_error("x ? 1 :").message(), "expected operand after `:`, found end of input");
+	}
+
+	#[test]
+	pub fn test_lambda() -> Result<()> {
+		assert_matches!(parse("x => x + 1")?, Value::Lambda(Lambda { ref parameters, ref body, .. })
+			if parameters[..] == [Pattern::Name("x".into())] && matches!(body.as_ref(), Value::Expression(_)));
+		assert_matches!(parse("( x, y ) => x * y")?, Value::Lambda(Lambda { ref parameters, .. }) if parameters[..] == [Pattern::Name("x".into()), Pattern::Name("y".into())]);
+		assert_matches!(parse("() => 1")?, Value::Lambda(Lambda { ref parameters, .. }) if parameters.is_empty());
+
+		// Lambdas nest to the right and end at the enclosing delimiter
+		assert_matches!(parse("x => y => x")?, Value::Lambda(Lambda { ref body, .. }) if matches!(body.as_ref(), Value::Lambda(_)));
+		assert_matches!(parse("map(list, x => x * 2)")?, Value::Call(Call { ref arguments, .. }) if matches!(arguments[1], Value::Lambda(_)));
+
+		// A parenthesised name is still just a value
+		assert_matches!(parse("(x)")?, Value::Literal(_));
+
+		assert_eq!(parse_error("x =>").message(), "expected operand after `=>`, found end of input");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_let() -> Result<()> {
+		assert_matches!(parse("let x = 1 + 2 in x * x")?, Value::Let(Let { ref bindings, ref body, .. })
+			if bindings.len() == 1 && bindings[0].0.name() == Some("x") && matches!(body.as_ref(), Value::Expression(_)));
+
+		assert_matches!(parse("let x = 1; y = x + 1; x + y")?, Value::Let(Let { ref bindings, .. })
+			if bindings.iter().map(|(pattern, _)| pattern.name()).eq([Some("x"), Some("y")]));
+		assert_same(parse("let x = 1; y = 2 in x")?, parse("let x = 1 ; y = 2 ; x")?);
+
+		// The body may itself start with a name and a comparison
+		assert_matches!(parse("let x = 1; x == 1")?, Value::Let(Let { ref bindings, .. }) if bindings.len() == 1);
+
+		// `in` ends the binding unless it is enclosed in delimiters
+		assert_matches!(parse("let f = x => x + 1 in f")?, Value::Let(Let { ref bindings, .. }) if matches!(bindings[0].1, Value::Lambda(_)));
+		assert_matches!(parse("let x = (1 in list) in x")?, Value::Let(Let { ref bindings, .. })
+			if matches!(bindings[0].1, Value::Expression(Expression { ref operator, .. }) if operator == "in"));
+
+		assert_matches!(parse("letter")?, Value::Literal(_));
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_let_errors() {
+		assert_eq!(parse_error("let x 1 in x").message(), "expected `=`, found `1`");
+		assert_eq!(parse_error("let x = 1").message(), "expected `in` or `;`, found end of input");
+	}
+
+	#[test]
+	pub fn test_postfix_chain() -> Result<()> {
+		assert_same(parse("a.b.c")?, Value::Access(Access {
+			left: Box::new(parse("a.b")?),
+			member: Literal::Name("c".to_owned()).into(),
+			optional: false,
+			span: Span::default(),
+		}));
+
+		assert_matches!(parse("f(x).y")?, Value::Access(Access { ref left, .. }) if matches!(left.as_ref(), Value::Call(_)));
+		assert_matches!(parse("list[i + 1]")?, Value::Index(Index { ref index, .. }) if matches!(index.as_ref(), Value::Expression(_)));
+		assert_matches!(parse("f(1)(2)")?, Value::Call(Call { ref name, .. }) if matches!(name.as_ref(), Value::Call(_)));
+		assert_matches!(parse("row.values.0")?, Value::Access(Access { ref member, .. }) if member.node == Literal::Number(0.0));
+		assert_eq!(parse("a.b[0](x).c")?.span().range(), 0..11);
+
+		// Whole numbers are read as separate members
+		assert_same(parse("list.0.1")?, parse("(list.0).1")?);
+
+		// Whitespace may separate indices and arguments from their value, except within a program
+		assert_same(parse("[1, 2] [0]")?, parse("[1, 2][0]")?);
+		assert_same(parse("max (1, 2)")?, parse("max(1, 2)")?);
+		assert_same(parse("f\n(x)\n[0]")?, parse("f(x)[0]")?);
+		assert_matches!(cx().parse_program("f\n[0]")?.statements.as_slice(), [Statement::Expression(Value::Literal(_)), Statement::Expression(Value::List(_))]);
+		assert_matches!(cx().parse_program("[max (1, 2)]; max(1, 2)")?.statements.as_slice(), [Statement::Expression(Value::List(_)), Statement::Expression(Value::Call(_))]);
+		assert_matches!(cx().parse_program("max (1, 2)"), Err(_));
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_numbers() -> Result<()> {
+		let number = |input| -> Result<f64> { match parse(input)? {
+			Value::Literal(Spanned { node: Literal::Number(number), .. }) => Ok(number),
+			other => panic!("Expected a number, got {:?}", other),
+		} };
+
+		assert_eq!(number("1.5")?, 1.5);
+		assert_eq!(number("2.5e3")?, 2500.0);
+		assert_eq!(number("1e-2")?, 0.01);
+		assert_eq!(number("0xff")?, 255.0);
+		assert_eq!(number("0o17")?, 15.0);
+		assert_eq!(number("0b101")?, 5.0);
+
+		// Each radix is read in its own base, and underscores may separate digits
+		assert_eq!(number("0x1F")?, 31.0);
+		assert_eq!(number("0x1f_ff")?, 8191.0);
+		assert_eq!(number("0o17")?, 15.0);
+		assert_eq!(number("0o1_0")?, 8.0);
+		assert_eq!(number("0b1_0")?, 2.0);
+
+		// Exponents may be signed and follow either an integer or a decimal
+		assert_eq!(number("1e-3")?, 0.001);
+		assert_eq!(number("1E+3")?, 1000.0);
+		assert_eq!(number("1_0.5e1")?, 105.0);
+		assert_eq!(number("4.2e-1")?, 0.42);
+
+		// Radix literals must fit in 64 bits, while decimal integers of any length are read as floats
+		assert_eq!(number("0xFFFF_FFFF_FFFF_FFFF")?, u64::MAX as f64);
+		assert_eq!(number("123456789012345678901234567890")?, 1.2345678901234568e29);
+		assert_eq!(parse_error("0xFFFFFFFFFFFFFFFFFF").message(), "expected a number which fits in 64 bits, found `0`");
+		assert_eq!(parse_error("1 + -0o7777777777777777777777").message(), "expected a number which fits in 64 bits, found `-`");
+		assert_eq!(parse_error(&format!("[0b{}]", "1".repeat(65))).location.column, 2);
+		assert_matches!(parse("let 0xFFFFFFFFFFFFFFFFFF = x in x"), Err(_));
+
+		assert_matches!(parse("true_value")?, Value::Literal(Spanned { node: Literal::Name(ref name), .. }) if name == "true_value");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_optional_chaining() -> Result<()> {
+		assert_matches!(parse("a?.b")?, Value::Access(Access { optional: true, ref member, .. }) if member.node == Literal::Name("b".to_owned()));
+		assert_matches!(parse("a?.[i]")?, Value::Index(Index { optional: true, .. }));
+		assert_matches!(parse("f?.(x)")?, Value::Call(Call { optional: true, .. }));
+		assert_matches!(parse("a?.b.c")?, Value::Access(Access { optional: false, ref left, .. }) if matches!(left.as_ref(), Value::Access(Access { optional: true, .. })));
+
+		// Neither `?.` nor `??` start a ternary
+		assert_matches!(parse("a ?? b")?, Value::Expression(Expression { ref operator, .. }) if operator == "??");
+		assert_matches!(parse("a ? b : c ?? d")?, Value::Conditional(_));
+
+		// A digit after `?.` makes it a ternary with a decimal
+		assert_same(parse("true ?.5 : 1")?, parse("true ? .5 : 1")?);
+		assert_same(parse("a?.5:b?.c")?, parse("a ? .5 : (b?.c)")?);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_pipeline() -> Result<()> {
+		assert_matches!(parse("x |> round(2)")?, Value::Pipeline(Pipeline { ref value, ref function, ref arguments, .. })
+			if matches!(value.as_ref(), Value::Literal(_)) && matches!(function.as_ref(), Value::Literal(_)) && arguments.len() == 1);
+		assert_matches!(parse("x |> f")?, Value::Pipeline(Pipeline { ref arguments, .. }) if arguments.is_empty());
+
+		// Stages apply left to right
+		assert_matches!(parse("x |> filter(f) |> sum()")?, Value::Pipeline(Pipeline { ref value, .. }) if matches!(value.as_ref(), Value::Pipeline(_)));
+
+		// Pipelines bind looser than operators on their left, and the result may be used with operators
+		assert_matches!(parse("a + b |> f")?, Value::Pipeline(Pipeline { ref value, .. }) if matches!(value.as_ref(), Value::Expression(_)));
+		assert_matches!(parse("x |> f > 2")?, Value::Expression(Expression { ref operands, .. }) if matches!(operands[0], Value::Pipeline(_)));
+		assert_matches!(parse("x |> f > 2 ? a : b")?, Value::Conditional(_));
+
+		assert_eq!(parse_error("x |>").message(), "expected operand after `|>`, found end of input");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_template() -> Result<()> {
+		assert_matches!(parse("`Total: ${sum(items)} items`")?, Value::Template(Template { ref segments, .. })
+			if matches!(segments.as_slice(), [Segment::Text(_), Segment::Value { value: Value::Call(_), format: None }, Segment::Text(_)]));
+		assert_matches!(parse("`${ratio:,.2%}`")?, Value::Template(Template { ref segments, .. })
+			if matches!(segments.as_slice(), [Segment::Value { format: Some(FormatSpec { thousands: true, precision: Some(2), percent: true }), .. }]));
+
+		// Ternaries and nested templates may be embedded
+		assert_matches!(parse("`${ok ? `yes` : 'no'}`")?, Value::Template(Template { ref segments, .. })
+			if matches!(segments.as_slice(), [Segment::Value { value: Value::Conditional(_), format: None }]));
+
+		assert_same(parse("`cost: $5 \\${x} \\``")?, Value::Template(Template {
+			segments: vec![Segment::Text("cost: $5 ${x} `".to_owned())],
+			span: Default::default(),
+		}));
+
+		assert_eq!(parse_error("`abc").message(), "expected closing ``` for template string opened at 1:1, found end of input");
+		assert_eq!(parse_error("`a ${1 + 2 b`").message(), "expected closing `}` for template placeholder opened at 1:4, found `b`");
+
+		// Formats may not be empty, and their precision is bounded
+		assert_matches!(parse("`${x:.100}`")?, Value::Template(_));
+		assert_eq!(parse_error("`${x:}`").message(), "expected a format such as `,.2%` with at most 100 decimal places, found `}`");
+		assert_eq!(parse_error("`${1:.4000000000}`").message(), "expected a format such as `,.2%` with at most 100 decimal places, found `.`");
+		assert_eq!(parse_error("`${1:.99999999999999999999999}`").location.column, 6);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_range() -> Result<()> {
+		assert_matches!(parse("1..10")?, Value::Expression(Expression { ref operator, ref operands, .. })
+			if operator == ".." && matches!(&operands[0], Value::Literal(literal) if literal.node == Literal::Number(1.0)));
+		assert_matches!(parse("1.5..n")?, Value::Expression(Expression { ref operands, .. })
+			if matches!(&operands[0], Value::Literal(literal) if literal.node == Literal::Number(1.5)));
+
+		// Ranges bind looser than arithmetic and tighter than membership
+		assert_matches!(parse("x in 0..n + 1 step 5")?, Value::Expression(Expression { ref operator, ref operands, .. })
+			if operator == "in" && matches!(operands[1], Value::Expression(Expression { ref operator, .. }) if operator == "step"));
+
+		assert_eq!(parse_error("1..2..3").message(), "expected parentheses around the first `..`, as it cannot be chained, found `.`");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_spread() -> Result<()> {
+		assert_matches!(parse("[...a, 4]")?, Value::List(List { ref items, .. })
+			if matches!(items.as_slice(), [Value::Spread(Spread { .. }), Value::Literal(_)]));
+		assert_matches!(parse("[...base, x = 1]")?, Value::AssociativeArray(AssociativeArray { ref items, .. })
+			if matches!(items.as_slice(), [Entry::Spread(_), Entry::Pair(Key::Name(_), _)]));
+
+		// Without any entries of its own, a spread is a list item
+		assert_matches!(parse("[...a]")?, Value::List(_));
+		assert_matches!(parse("[]")?, Value::AssociativeArray(_));
+
+		assert_matches!(parse("f(...args, ...[1 .. 2])")?, Value::Call(Call { ref arguments, .. }) if arguments.len() == 2);
+		assert_eq!(parse_error("[1, ...]").message(), "expected operand after `...`, found `]`");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_named_arguments() -> Result<()> {
+		assert_matches!(parse("round(x, precision = 2)")?, Value::Call(Call { ref arguments, ref named, .. })
+			if arguments.len() == 1 && matches!(named.as_slice(), [(name, Value::Literal(_))] if name == "precision"));
+
+		// Comparisons and lambdas are still positional
+		assert_matches!(parse("f(x == 2, x => x)")?, Value::Call(Call { ref arguments, ref named, .. }) if arguments.len() == 2 && named.is_empty());
+
+		assert_matches!(parse("x |> round(precision = 2)")?, Value::Pipeline(Pipeline { ref arguments, ref named, .. }) if arguments.is_empty() && named.len() == 1);
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_match() -> Result<()> {
+		assert_matches!(parse("match x { number n => n, 'a' => 1, [a, _] => a, [k = v] => v, nothing => 0, y => y, }")?, Value::Match(Match { ref arms, .. })
+			if matches!(arms.iter().map(|(pattern, _)| pattern).collect::<Vec<_>>().as_slice(), [
+				Pattern::Type { name: Some(_), .. },
+				Pattern::Literal(Literal::String(_)),
+				Pattern::List { items: list, rest: None },
+				Pattern::AssociativeArray { .. },
+				Pattern::Literal(Literal::Nothing),
+				Pattern::Name(_),
+			] if list[1] == Pattern::Wildcard));
+
+		// Arm bodies may contain lambdas and further matches
+		assert_matches!(parse("match f { function _ => x => x, _ => match g { _ => 1 } }")?, Value::Match(Match { ref arms, .. })
+			if matches!(arms[0], (Pattern::Type { name: None, .. }, Value::Lambda(_))) && matches!(arms[1].1, Value::Match(_)));
+
+		assert_eq!(parse_error("match x { 1 2 }").message(), "expected `=>`, found `2`");
+		assert_eq!(parse_error("match x { ] }").message(), "expected a pattern, found `]`");
+		assert_eq!(parse_error("match x { _ => 1").message(), "expected closing `}` for match opened at 1:9, found end of input");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_destructuring() -> Result<()> {
+		assert_matches!(parse("let [first, second ?? 0, ...rest] = xs in first")?, Value::Let(Let { ref bindings, .. })
+			if matches!(&bindings[0].0, Pattern::List { items, rest: Some(rest) }
+				if matches!(items[1], Pattern::Default { .. }) && rest.name() == Some("rest")));
+
+		assert_matches!(parse("([x = a, y = b ?? 1, ...others]) => a + b")?, Value::Lambda(Lambda { ref parameters, .. })
+			if matches!(&parameters[0], Pattern::AssociativeArray { entries, rest: Some(_) } if entries.len() == 2));
+
+		// Parameters may be given defaults, and are still names when they share a datatype's name
+		assert_matches!(parse("(list, string, n ?? 1) => list")?, Value::Lambda(Lambda { ref parameters, .. })
+			if parameters.iter().map(Pattern::name).eq([Some("list"), Some("string"), Some("n")]));
+
+		// Parenthesised expressions aren't mistaken for parameters
+		assert_matches!(parse("(a ?? [1, 2])")?, Value::Expression(_));
+
+		assert_eq!(parse_error("let [a, ...] = x in a").message(), "expected a pattern, found `]`");
+		assert_eq!(parse_error("let [a ?? ] = x in a").message(), "expected operand after `??`, found `]`");
+
+		Ok(())
+	}
+
+	#[test]
+	pub fn test_program() -> Result<()> {
+		let program = cx().parse_program("let rate = 0.2\nlet tax(price, r ?? rate) = price * r\n\nlet [a, b] = [1, 2]; tax(a)\n\t+ b;")?;
+
+		assert_matches!(program.statements.as_slice(), [
+			Statement::Binding(Pattern::Name(_), Value::Literal(_)),
+			Statement::Function { name, lambda: Lambda { parameters, .. } },
+			Statement::Binding(Pattern::List { .. }, _),
+			Statement::Expression(Value::Expression(_)),
+		] if name == "tax" && parameters.len() == 2);
+
+		// A `let` with its own body is an expression rather than a binding
+		assert_matches!(cx().parse_program("let x = 1 in x\nx")?.statements.as_slice(), [Statement::Expression(Value::Let(_)), Statement::Expression(_)]);
+		assert_matches!(cx().parse_program("let x = 1; y = 2; x + y")?.statements.as_slice(), [
+			Statement::Binding(Pattern::Name(_), _),
+			Statement::Binding(Pattern::Name(_), _),
+			Statement::Expression(Value::Expression(Expression { fixity: Fixity::Infix, .. })),
+		]);
+
+		// Only operators which can't be a prefix continue the statement on the line before them
+		assert_matches!(cx().parse_program("let a = 5\n-a")?.statements.as_slice(), [
+			Statement::Binding(_, Value::Literal(_)),
+			Statement::Expression(Value::Expression(Expression { fixity: Fixity::Prefix, .. })),
+		]);
+		assert_matches!(cx().parse_program("a\n!b; [a\n-b]")?.statements.as_slice(), [
+			Statement::Expression(Value::Literal(_)),
+			Statement::Expression(Value::Expression(Expression { fixity: Fixity::Prefix, .. })),
+			Statement::Expression(Value::List(_)),
+		]);
+		assert_matches!(cx().parse_program("a -\nb")?.statements.as_slice(), [Statement::Expression(Value::Expression(_))]);
+		assert!(cx().parse_program(" // nothing here\n")?.statements.is_empty());
+
+		let program_error = |input: &str| match cx().parse_program(input).map_err(Error::into_inner) {
+			Err(global::Inner::ParserError(err)) => err.message(),
+			other => panic!("Expected a parse error, got {:?}", other),
+		};
+
+		assert_eq!(program_error("let x = 1 x"), "expected `;` or a new line, found `x`");
+		assert_eq!(program_error("let f(x) =\n"), "expected operand after `=`, found end of input");
 
 		Ok(())
 	}
-}
\ No newline at end of file
+}
@@ -1,73 +1,382 @@
+use alloc::boxed::Box;
+use alloc::vec;
 use alloc::vec::Vec;
 use crate::{
-    parse::associative_array::AssociativeArray,
+    parse::associative_array::{AssociativeArray, Entry},
     parse::call::Call,
-    parse::expression::Expression,
+    parse::binding::Let,
+    parse::conditional::Conditional,
+    parse::lambda::Lambda,
+    parse::expression::{Associativity, Expression, Fixity},
     parse::list::List,
+    parse::matching::Match,
+    parse::pattern::Pattern,
+    parse::pipeline::Pipeline,
+    parse::spread::Spread,
+    parse::template::{Segment, Template},
     parse::literal::Literal,
     parse::parser,
+    parse::span::{Span, Spanned},
     parse::ParseContext,
 };
-use core::ops::Deref;
 use nom::IResult;
-use crate::parse::access::Access;
+use crate::parse::access::{Access, Index};
+use crate::parse::error::{closing, expect, Expected};
+use crate::parse::whitespace::{token, whitespace};
 
 #[derive(Debug, PartialEq)]
 pub enum Value {
     Expression(Expression),
-    Literal(Literal),
+    Literal(Spanned<Literal>),
     Call(Call),
     Access(Access),
     List(List),
     AssociativeArray(AssociativeArray),
+    Conditional(Conditional),
+    Lambda(Lambda),
+    Let(Let),
+    Index(Index),
+    Pipeline(Pipeline),
+    Template(Template),
+    Spread(Spread),
+    Match(Match),
 }
 
-pub(super) fn value_parser<'a>(cx: ParseContext) -> impl Fn(&'a str) -> IResult<&'a str, Value> {
-    fn expr(rank: usize, cx: &ParseContext) -> impl Fn(&str) -> IResult<&str, Value> + '_ {
-        move |input| {
-            if let Some(operators) = cx.precedences.get(rank)
-                .and_then(|precedence| cx.operators.get(precedence))
-                .map(|i| i.iter()
-                    .map(|i| i.as_str())
-                    .collect::<Vec<&str>>()) {
-                //  expr(p=p+1) [operators[p]] expr(p=p) | expr(p+1)
-
-                parser::alt((
-                    parser::map(parser::tuple((expr(rank + 1, cx), one_of(&operators), expr(rank, cx))), Expression::build_value),
-                    expr(rank + 1, cx),
-                ))(input)
-            } else {
-                // ( expr(p=0) ) | Literal | Call | Access | List | AssociativeArray
-
-                parser::alt((
-                    parser::map(|input| Access::parse(input, cx.clone()), Value::Access),
-                    parser::map(|input| AssociativeArray::parse(input, cx.clone()), Value::AssociativeArray),
-                    parser::map(|input| List::parse(input, cx.clone()), Value::List),
-                    parser::map(|input| Call::parse(input, cx.clone()), Value::Call),
-                    parser::map(Literal::parse, Value::Literal),
-                    parser::delimited(parser::char('('), expr(0, cx), parser::char(')')),
-                ))(input)
-            }
+impl Value {
+    /// The region of the source this value was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Value::Expression(expr) => expr.span,
+            Value::Literal(literal) => literal.span,
+            Value::Call(call) => call.span,
+            Value::Access(access) => access.span,
+            Value::List(list) => list.span,
+            Value::AssociativeArray(arr) => arr.span,
+            Value::Conditional(conditional) => conditional.span,
+            Value::Lambda(lambda) => lambda.span,
+            Value::Let(binding) => binding.span,
+            Value::Index(index) => index.span,
+            Value::Pipeline(pipeline) => pipeline.span,
+            Value::Template(template) => template.span,
+            Value::Spread(spread) => spread.span,
+            Value::Match(matching) => matching.span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Value::Expression(expr) => &mut expr.span,
+            Value::Literal(literal) => &mut literal.span,
+            Value::Call(call) => &mut call.span,
+            Value::Access(access) => &mut access.span,
+            Value::List(list) => &mut list.span,
+            Value::AssociativeArray(arr) => &mut arr.span,
+            Value::Conditional(conditional) => &mut conditional.span,
+            Value::Lambda(lambda) => &mut lambda.span,
+            Value::Let(binding) => &mut binding.span,
+            Value::Index(index) => &mut index.span,
+            Value::Pipeline(pipeline) => &mut pipeline.span,
+            Value::Template(template) => &mut template.span,
+            Value::Spread(spread) => &mut spread.span,
+            Value::Match(matching) => &mut matching.span,
         }
     }
 
-    move |input| expr(0, &cx)(input)
+    /// The values directly nested within this one in source order, including the defaults of patterns.
+    pub fn children(&self) -> Vec<&Value> {
+        match self {
+            Value::Expression(expr) => expr.operands.iter().collect(),
+            Value::Literal(_) => vec![],
+            Value::Call(call) => core::iter::once(call.name.as_ref())
+                .chain(call.arguments.iter())
+                .chain(call.named.iter().map(|(_, value)| value))
+                .collect(),
+            Value::Spread(spread) => vec![spread.value.as_ref()],
+            Value::Match(matching) => core::iter::once(matching.value.as_ref())
+                .chain(matching.arms.iter().flat_map(|(pattern, value)| pattern.defaults().into_iter().chain([value])))
+                .collect(),
+            Value::Access(access) => vec![access.left.as_ref()],
+            Value::Index(index) => vec![index.left.as_ref(), index.index.as_ref()],
+            Value::Pipeline(pipeline) => [pipeline.value.as_ref(), pipeline.function.as_ref()].into_iter()
+                .chain(pipeline.arguments.iter())
+                .chain(pipeline.named.iter().map(|(_, value)| value))
+                .collect(),
+            Value::Template(template) => template.segments.iter()
+                .filter_map(|segment| match segment {
+                    Segment::Value { value, .. } => Some(value),
+                    Segment::Text(_) => None,
+                })
+                .collect(),
+            Value::List(list) => list.items.iter().collect(),
+            Value::AssociativeArray(arr) => arr.items.iter()
+                .map(Entry::value)
+                .collect(),
+            Value::Conditional(conditional) => vec![conditional.condition.as_ref(), conditional.then.as_ref(), conditional.otherwise.as_ref()],
+            Value::Lambda(lambda) => lambda.parameters.iter()
+                .flat_map(Pattern::defaults)
+                .chain(core::iter::once(lambda.body.as_ref()))
+                .collect(),
+            Value::Let(binding) => binding.bindings.iter()
+                .flat_map(|(pattern, value)| pattern.defaults().into_iter().chain([value]))
+                .chain(core::iter::once(binding.body.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// # Node Lookup
+    /// Finds the innermost value whose span contains the byte `offset`. This is what editor integrations use to
+    /// resolve the node under the cursor for highlighting, hover and error reporting.
+    ///
+    /// ```rust
+    /// use expression::Context;
+    /// use expression::EmptyProvider;
+    /// use expression::Value;
+    ///
+    /// let cx = Context::new(EmptyProvider::new());
+    /// let ast = cx.parse("1+sin(2)").unwrap();
+    ///
+    /// assert_eq!(ast.node_at(6).map(|node| node.span().range()), Some(6..7));
+    /// ```
+    pub fn node_at(&self, offset: usize) -> Option<&Value> {
+        if !self.span().contains(offset) {
+            return None;
+        }
+
+        self.children()
+            .into_iter()
+            .find_map(|child| child.node_at(offset))
+            .or(Some(self))
+    }
+}
+
+/// Parses a value enclosed in delimiters, such as a list item. Words reserved by an enclosing construct are operators
+/// again within the delimiters, and new lines are whitespace even within a statement.
+pub(super) fn value_parser<'a>(cx: ParseContext) -> impl Fn(&'a str) -> IResult<&'a str, Value> {
+    move |input| {
+        let terminators = core::mem::take(&mut *cx.terminators.borrow_mut());
+        let statement = cx.statement.replace(false);
+        let result = conditional(input, &cx);
+        *cx.terminators.borrow_mut() = terminators;
+        cx.statement.set(statement);
+
+        result
+    }
 }
 
-fn one_of<'a, 'b, Iter: Deref<Target=[&'a str]> + 'a>(items: &'a Iter) -> impl Fn(&'b str) -> IResult<&'b str, &'b str> + 'a {
-    // fn one_of<'a>(items: &'a [&'a str]) -> Box<dyn Fn(&'a str) -> IResult<&'a str, &str>> {
-    |input| {
-        let mut last_err = None;
-
-        for &alt in items.deref().iter() {
-            match nom::bytes::complete::tag(alt)(input) {
-                Ok(result) => return Ok(result),
-                Err(error) => {
-                    last_err = Some(error);
-                }
+/// Parses a value which extends as far right as possible, such as a lambda body, and therefore ends wherever the
+/// construct enclosing it does.
+pub(super) fn open_value_parser<'a>(cx: ParseContext) -> impl Fn(&'a str) -> IResult<&'a str, Value> {
+    move |input| conditional(input, &cx)
+}
+
+/// expr | expr ? value : value
+///
+/// The ternary binds looser than every operator, and nests to the right so `a ? b : c ? d : e` chooses between
+/// three values.
+fn conditional<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+    let (input, _) = whitespace(input)?;
+    let (remaining, condition) = expression(input, cx, i64::MIN)?;
+
+    // `??` and `?.` are operators in their own right rather than the start of a ternary.
+    let Ok((after_question, _)) = token(|input| parser::preceded(parser::not(parser::alt((parser::tag("??"), optional_link))), parser::char('?'))(input))(remaining) else {
+        return Ok((remaining, condition));
+    };
+
+    let (remaining, (then, _, otherwise)) = parser::tuple((
+        expect(cx, Expected::Operand("?"), |input| conditional(input, cx)),
+        expect(cx, Expected::Token(":"), token(parser::char(':'))),
+        expect(cx, Expected::Operand(":"), |input| conditional(input, cx)),
+    ))(after_question)?;
+
+    Ok((remaining, Value::Conditional(Conditional {
+        condition: Box::new(condition),
+        then: Box::new(then),
+        otherwise: Box::new(otherwise),
+        span: cx.span(input, remaining),
+    })))
+}
+
+/// Parses an operand followed by any operators binding at least as tightly as `min_precedence`.
+///
+/// The right-hand side of a left-associative operator only accepts operators binding strictly tighter than it, which
+/// leaves the next operator of equal precedence to this loop, grouping the chain to the left. Right-associative
+/// operators accept their own precedence on the right-hand side, grouping to the right.
+fn expression<'a>(input: &'a str, cx: &ParseContext, min_precedence: i64) -> IResult<&'a str, Value> {
+    let (input, _) = whitespace(input)?;
+    let (mut remaining, mut left) = operand(input, cx)?;
+
+    // The precedence of the last non-associative operator applied, which may not be immediately chained.
+    let mut non_associative = None;
+
+    loop {
+        let (before_operator, _) = whitespace(remaining)?;
+
+        // `-` and `!` on a new line start the next statement of a program rather than continuing this one.
+        if cx.statement.get()
+            && remaining[..remaining.len() - before_operator.len()].contains('\n')
+            && cx.operator(before_operator, Fixity::Prefix).is_some() {
+            return Ok((remaining, left));
+        }
+
+        // Pipelines bind looser than every operator, so `a + b |> f` is `f(a + b)`.
+        if min_precedence == i64::MIN {
+            if let Ok((after_pipe, _)) = parser::tag::<_, _, nom::error::Error<&str>>("|>")(before_operator) {
+                let (after_stage, stage) = expect(cx, Expected::Operand("|>"), |input| {
+                    let (input, _) = whitespace(input)?;
+                    primary(input, cx)
+                })(after_pipe)?;
+
+                non_associative = None;
+                left = Value::Pipeline(Pipeline::new(left, stage, cx.span(input, after_stage)));
+                remaining = after_stage;
+                continue;
             }
         }
 
-        Err(last_err.unwrap_or(nom::Err::Error(nom::error::Error { input, code: nom::error::ErrorKind::NonEmpty })))
+        if let Some((after_operator, operator)) = cx.operator(before_operator, Fixity::Infix)
+            .filter(|(_, operator)| operator.precedence >= min_precedence) {
+            let symbol = &before_operator[..operator.symbol.len()];
+
+            if non_associative == Some(operator.precedence) {
+                cx.record(before_operator, Expected::Grouping(symbol));
+                return Err(nom::Err::Failure(nom::error::Error::new(before_operator, nom::error::ErrorKind::Verify)));
+            }
+
+            let right_precedence = match operator.associativity {
+                Associativity::Right => operator.precedence,
+                Associativity::Left | Associativity::None => operator.precedence.saturating_add(1),
+            };
+            let right = expect(cx, Expected::Operand(symbol), |input| expression(input, cx, right_precedence))(after_operator);
+
+            // An infix operator without a right-hand side may still be a postfix operator, otherwise the expression ends here.
+            if let Ok((after_right, right)) = right {
+                non_associative = (operator.associativity == Associativity::None).then_some(operator.precedence);
+                left = Expression::infix(left, symbol, right, cx.span(input, after_right));
+                remaining = after_right;
+                continue;
+            }
+        }
+
+        if let Some((after_operator, operator)) = cx.operator(before_operator, Fixity::Postfix)
+            .filter(|(_, operator)| operator.precedence >= min_precedence) {
+            non_associative = None;
+            left = Expression::unary(&operator.symbol, Fixity::Postfix, left, cx.span(input, after_operator));
+            remaining = after_operator;
+            continue;
+        }
+
+        return Ok((remaining, left));
+    }
+}
+
+/// A primary value, or a prefix operator applied to an operand.
+fn operand<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+    let prefix = |err| {
+        let Some((after_operator, operator)) = cx.operator(input, Fixity::Prefix) else {
+            return Err(err);
+        };
+
+        let symbol = &input[..operator.symbol.len()];
+        let (remaining, operand) = expect(cx, Expected::Operand(symbol), |input| expression(input, cx, operator.precedence))(after_operator)?;
+
+        Ok((remaining, Expression::unary(symbol, Fixity::Prefix, operand, cx.span(input, remaining))))
+    };
+
+    // Prefix word operators such as `not` would otherwise be read as names.
+    if cx.operator(input, Fixity::Prefix).is_some_and(|(_, operator)| operator.is_word()) {
+        return prefix(nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Tag)));
+    }
+
+    // Failures such as a literal which is too large are final, so `-0x…` isn't retried as a negation
+    match primary(input, cx) {
+        Err(nom::Err::Error(err)) => prefix(nom::Err::Error(err)),
+        result => result,
+    }
+}
+
+/// atom ( .member | [value] | (arguments) | ?.member | ?.[value] | ?.(arguments) )*
+///
+/// Within a program, indices and arguments must immediately follow the value they apply to, so a list on the next line
+/// is never mistaken for an index.
+fn primary<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+    let (mut remaining, mut value) = atom(input, cx)?;
+
+    loop {
+        // Optional links evaluate to `nothing` rather than failing when the value before them is `nothing`
+        let (link, optional) = match token(optional_link)(remaining) {
+            Ok((link, _)) => (link, true),
+            Err(_) if cx.statement.get() => (remaining, false),
+            Err(_) => (whitespace(remaining)?.0, false),
+        };
+
+        let member = match optional {
+            true => Spanned::parse(link, cx, Access::name),
+            false => Access::member(link, cx),
+        };
+
+        (remaining, value) = if let Ok((after, member)) = member {
+            (after, Value::Access(Access { left: Box::new(value), member, optional, span: cx.span(input, after) }))
+        } else if let Ok((after, index)) = Index::index(link, cx) {
+            (after, Value::Index(Index { left: Box::new(value), index: Box::new(index), optional, span: cx.span(input, after) }))
+        } else if let Ok((after, (arguments, named))) = Call::arguments(link, cx) {
+            (after, Value::Call(Call { name: Box::new(value), arguments, named, optional, span: cx.span(input, after) }))
+        } else {
+            return Ok((remaining, value));
+        };
     }
-}
\ No newline at end of file
+}
+
+/// `?.`, unless a digit follows as in `a ?.5 : b`, which is a ternary choosing `.5` just as in JavaScript.
+fn optional_link(input: &str) -> IResult<&str, &str> {
+    parser::terminated(parser::tag("?."), parser::not(parser::satisfy(|char| char.is_ascii_digit())))(input)
+}
+
+/// ( value ) | Conditional | Let | Match | Lambda | List | AssociativeArray | Template | Literal
+fn atom<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+    parser::alt((
+        parser::map(|input| Conditional::parse(input, cx.clone()), Value::Conditional),
+        parser::map(|input| Let::parse(input, cx.clone()), Value::Let),
+        parser::map(|input| Match::parse(input, cx.clone()), Value::Match),
+        parser::map(|input| Lambda::parse(input, cx.clone()), Value::Lambda),
+        parser::map(|input| AssociativeArray::parse(input, cx.clone()), Value::AssociativeArray),
+        parser::map(|input| List::parse(input, cx.clone()), Value::List),
+        parser::map(|input| Template::parse(input, cx.clone()), Value::Template),
+        parser::map(|input| Spanned::parse(input, cx, literal(cx)), Value::Literal),
+        |input| parenthesised(input, cx),
+    ))(input).inspect_err(|_| unterminated(input, cx))
+}
+
+/// ( value ), whose span includes the parentheses so that looking up the node at either of them finds the value.
+fn parenthesised<'a>(input: &'a str, cx: &ParseContext) -> IResult<&'a str, Value> {
+    let (remaining, mut value) = parser::preceded(
+        parser::char('('),
+        parser::terminated(value_parser(cx.clone()), closing(cx, ')', "parenthesised expression", input)),
+    )(input)?;
+
+    *value.span_mut() = cx.span(input, remaining);
+    Ok((remaining, value))
+}
+
+/// A literal, reporting numbers which are too large where they start.
+fn literal(cx: &ParseContext) -> impl Fn(&str) -> IResult<&str, Literal> + '_ {
+    move |input| Literal::parse(input).inspect_err(|err| if let nom::Err::Failure(_) = err {
+        cx.record(input, Expected::Number);
+    })
+}
+
+/// Strings and addresses consume everything up to their closing delimiter, so when one is left open the error is
+/// reported at the end of the input rather than at the opening quote.
+fn unterminated(input: &str, cx: &ParseContext) {
+    let construct = match input.chars().next() {
+        Some('"') => ('"', "string"),
+        Some('\'') => ('\'', "string"),
+        Some('{') => ('}', "address"),
+        _ => return cx.record(input, Expected::Expression),
+    };
+
+    cx.record(&input[input.len()..], Expected::Closing {
+        delimiter: construct.0,
+        construct: construct.1,
+        opened: input,
+    });
+}
@@ -0,0 +1,19 @@
+use crate::parse::parser;
+use nom::IResult;
+
+/// Skips any amount of whitespace (including newlines), `// line` comments and `/* block */` comments.
+pub(crate) fn whitespace(input: &str) -> IResult<&str, ()> {
+    parser::value((), parser::many0_count(parser::alt((
+        parser::multispace1,
+        parser::recognize(parser::pair(parser::tag("//"), parser::opt(parser::is_not("\n")))),
+        parser::recognize(parser::tuple((parser::tag("/*"), parser::take_until("*/"), parser::tag("*/")))),
+    ))))(input)
+}
+
+/// Parses `parser` after skipping any leading whitespace and comments.
+pub(crate) fn token<'a, O>(parser: impl Fn(&'a str) -> IResult<&'a str, O>) -> impl Fn(&'a str) -> IResult<&'a str, O> {
+    move |input| {
+        let (input, _) = whitespace(input)?;
+        parser(input)
+    }
+}